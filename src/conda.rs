@@ -0,0 +1,160 @@
+//! Conda is a cross-language package and environment manager, where an environment.yml file
+//! declares the conda packages and a nested `pip:` section declares packages from PyPI.
+//! This module parses the environment.yml file to get package dependencies.
+use std::{
+    collections::HashSet,
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+    string::String,
+};
+
+use serde::Deserialize;
+
+use crate::requirements::get_dependencies_from_requirements_lines;
+
+/// The file names of a conda environment file, in order of preference
+pub const ENVIRONMENT_FILES: [&str; 2] = ["environment.yml", "environment.yaml"];
+
+/// Conda packages that provide the interpreter, compilers, system libraries or tools rather
+/// than an importable Python package, so they can never be matched with an import statement
+const NON_IMPORTABLE_PACKAGES: [&str; 24] = [
+    "python",
+    "pip",
+    "setuptools",
+    "wheel",
+    "cudatoolkit",
+    "cudnn",
+    "nccl",
+    "pytorch-cuda",
+    "cuda-version",
+    "mkl",
+    "openblas",
+    "blas",
+    "libblas",
+    "liblapack",
+    "openssl",
+    "nodejs",
+    "git",
+    "make",
+    "cmake",
+    "compilers",
+    "c-compiler",
+    "cxx-compiler",
+    "ffmpeg",
+    "graphviz",
+];
+
+/// Prefixes of conda packages that are never importable Python packages, e.g. R packages
+const NON_IMPORTABLE_PREFIXES: [&str; 3] = ["r-", "cuda-", "_"];
+
+#[derive(Deserialize, Debug)]
+struct Environment {
+    #[serde(default)]
+    dependencies: Vec<CondaDependency>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum CondaDependency {
+    Package(String),
+    Pip { pip: Vec<String> },
+}
+
+/// Reads the environment.yml file and returns the dependencies, excluding the conda packages
+/// that are not importable. Conda has no notion of dev dependencies
+pub fn get_dependencies_from_environment(
+    environment_path: &PathBuf,
+) -> Result<HashSet<String>, Box<dyn Error>> {
+    let environment: Environment = serde_yaml_ng::from_str(&fs::read_to_string(environment_path)?)?;
+    let parent = environment_path.parent().unwrap_or(Path::new("."));
+
+    let mut dependencies: HashSet<String> = HashSet::new();
+    for dependency in environment.dependencies.iter() {
+        match dependency {
+            CondaDependency::Package(spec) => {
+                let name = conda_package_name(spec);
+                if is_importable(name) {
+                    dependencies.insert(name.to_string());
+                }
+            }
+            CondaDependency::Pip { pip } => {
+                // the `pip:` section is passed to pip as a requirements file
+                dependencies.extend(get_dependencies_from_requirements_lines(pip, parent)?);
+            }
+        }
+    }
+
+    Ok(dependencies)
+}
+
+/// Returns the package name of a conda match spec, e.g. `pytorch` for `pytorch::pytorch>=2.1`
+/// or `pandas` for `pandas 2.1.*`
+fn conda_package_name(spec: &str) -> &str {
+    let spec = spec.rsplit("::").next().unwrap_or(spec).trim();
+    let end = spec
+        .find(|c: char| c.is_whitespace() || matches!(c, '=' | '<' | '>' | '!' | '~' | '['))
+        .unwrap_or(spec.len());
+    &spec[..end]
+}
+
+fn is_importable(name: &str) -> bool {
+    let name = name.to_lowercase();
+    !NON_IMPORTABLE_PACKAGES.contains(&name.as_str())
+        && !NON_IMPORTABLE_PREFIXES
+            .iter()
+            .any(|prefix| name.starts_with(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_dependencies_from_environment() {
+        let environment_path: PathBuf =
+            PathBuf::from("tests/fixtures/example_conda_project/environment.yml");
+        let dependencies = get_dependencies_from_environment(&environment_path)
+            .expect("expected to get deps from environment.yml");
+        let expected: HashSet<String> = [
+            "pytorch".to_string(),
+            "numpy".to_string(),
+            "pandas".to_string(),
+            "scipy".to_string(),
+            "matplotlib-base".to_string(),
+            "transformers".to_string(),
+            "wandb".to_string(),
+            "lightgbm".to_string(),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+        assert_eq!(dependencies, expected);
+    }
+
+    #[test]
+    fn test_get_dependencies_from_environment_with_pip_urls_and_paths() {
+        let environment_path: PathBuf =
+            PathBuf::from("tests/fixtures/input/conda_pip/environment.yml");
+        let dependencies = get_dependencies_from_environment(&environment_path)
+            .expect("expected to get deps from environment.yml");
+        let expected = HashSet::from(["requests".to_string(), "acme-sdk".to_string()]);
+        assert_eq!(dependencies, expected);
+    }
+
+    #[test]
+    fn test_conda_package_name() {
+        assert_eq!(conda_package_name("python=3.11"), "python");
+        assert_eq!(conda_package_name("pytorch::pytorch>=2.1"), "pytorch");
+        assert_eq!(conda_package_name("pandas 2.1.* py311_0"), "pandas");
+        assert_eq!(conda_package_name("numpy[version='>=1.26']"), "numpy");
+    }
+
+    #[test]
+    fn test_is_importable() {
+        assert!(is_importable("numpy"));
+        assert!(!is_importable("cudatoolkit"));
+        assert!(!is_importable("r-base"));
+        assert!(!is_importable("_libgcc_mutex"));
+    }
+}
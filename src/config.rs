@@ -0,0 +1,184 @@
+//! deptr reads its settings from a deptr.toml file in the project directory or, when there is
+//! none, from the `[tool.deptr]` table of pyproject.toml. Both have the same keys, e.g.
+//! ```toml
+//! [tool.deptr]
+//! ignore = ["gunicorn", { package = "psycopg2-binary", reason = "loaded by SQLAlchemy" }]
+//! exclude = ["docs", "scripts/legacy"]
+//! dev = true
+//! format = "json"
+//! src = ["src"]
+//!
+//! [tool.deptr.module-map]
+//! my-internal-sdk = ["acme_sdk", "acme_tools"]
+//! ```
+//! The command line flags take precedence over the settings of the file.
+use std::{collections::HashMap, error::Error, fs, path::Path};
+
+use clap::ValueEnum;
+use serde::Deserialize;
+
+/// The standalone configuration file, which takes precedence over pyproject.toml
+pub const CONFIG_FILE: &str = "deptr.toml";
+
+/// The format the results are printed in
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// A package, or module, left out of every report, with why it is ignored
+#[derive(Clone, Deserialize, Debug, PartialEq)]
+#[serde(from = "IgnoreEntry")]
+pub struct IgnoredPackage {
+    pub package: String,
+    pub reason: Option<String>,
+}
+
+impl IgnoredPackage {
+    pub fn new(package: String) -> Self {
+        Self {
+            package,
+            reason: None,
+        }
+    }
+}
+
+/// An ignore entry is either the package name or a table with the package name and a reason
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum IgnoreEntry {
+    Package(String),
+    WithReason {
+        package: String,
+        reason: Option<String>,
+    },
+}
+
+impl From<IgnoreEntry> for IgnoredPackage {
+    fn from(entry: IgnoreEntry) -> Self {
+        match entry {
+            IgnoreEntry::Package(package) => IgnoredPackage::new(package),
+            IgnoreEntry::WithReason { package, reason } => IgnoredPackage { package, reason },
+        }
+    }
+}
+
+/// The settings of a project, all of them optional
+#[derive(Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct FileConfig {
+    /// Packages, or modules, left out of every report
+    #[serde(default)]
+    pub ignore: Vec<IgnoredPackage>,
+    /// Directories excluded from the scan for imports, by name or by path
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Whether to track dev dependencies
+    pub dev: Option<bool>,
+    pub format: Option<OutputFormat>,
+    /// The source roots scanned for imports, relative to the project path
+    #[serde(default)]
+    pub src: Vec<String>,
+    /// The top level import names of packages, keyed by the package name, for packages the
+    /// import name database does not know
+    #[serde(default)]
+    pub module_map: HashMap<String, Vec<String>>,
+}
+
+#[derive(Deserialize, Debug)]
+struct PyProjectToml {
+    tool: Option<Tool>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Tool {
+    deptr: Option<FileConfig>,
+}
+
+/// Reads the settings of the project from deptr.toml, otherwise from the `[tool.deptr]` table
+/// of pyproject.toml. A project without either has the default settings
+pub fn load_config(project_path: &Path) -> Result<FileConfig, Box<dyn Error>> {
+    let config_path = project_path.join(CONFIG_FILE);
+    if config_path.is_file() {
+        let config = toml::from_str(&fs::read_to_string(&config_path)?)
+            .map_err(|e| format!("Invalid {}: {}", CONFIG_FILE, e))?;
+        return Ok(config);
+    }
+
+    let toml_path = project_path.join("pyproject.toml");
+    if !toml_path.is_file() {
+        return Ok(FileConfig::default());
+    }
+    let pyproject: PyProjectToml = toml::from_str(&fs::read_to_string(&toml_path)?)
+        .map_err(|e| format!("Invalid [tool.deptr] in pyproject.toml: {}", e))?;
+
+    Ok(pyproject
+        .tool
+        .and_then(|tool| tool.deptr)
+        .unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_config_from_pyproject() {
+        let project_path = Path::new("tests/fixtures/input/config_pyproject");
+        let config = load_config(project_path).expect("expected a valid config");
+        assert_eq!(
+            config,
+            FileConfig {
+                ignore: vec![
+                    IgnoredPackage::new("gunicorn".to_string()),
+                    IgnoredPackage {
+                        package: "uvicorn".to_string(),
+                        reason: Some("started by the Procfile".to_string()),
+                    },
+                ],
+                exclude: vec!["scripts".to_string()],
+                dev: Some(true),
+                format: None,
+                src: vec!["app".to_string(), "tests".to_string()],
+                module_map: HashMap::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_load_config_from_deptr_toml() {
+        let project_path = Path::new("tests/fixtures/input/config_file");
+        let config = load_config(project_path).expect("expected a valid config");
+        assert_eq!(
+            config.ignore,
+            vec![IgnoredPackage::new("boto3".to_string())]
+        );
+        assert_eq!(config.format, Some(OutputFormat::Json));
+        assert_eq!(config.dev, None);
+        assert_eq!(
+            config.module_map.get("acme-client"),
+            Some(&vec!["acme".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_load_config_module_map_from_pyproject() {
+        let project_path = Path::new("tests/fixtures/input/module_map");
+        let config = load_config(project_path).expect("expected a valid config");
+        assert_eq!(config.module_map.len(), 2);
+        assert_eq!(
+            config.module_map.get("My_Internal.SDK"),
+            Some(&vec!["acme_sdk".to_string(), "acme_tools".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_load_config_without_config() {
+        let project_path = Path::new("tests/fixtures/input/missing_imports");
+        let config = load_config(project_path).expect("expected a valid config");
+        assert_eq!(config, FileConfig::default());
+    }
+}
@@ -0,0 +1,267 @@
+//! Detects the package manager of a Python project from the files in the project directory:
+//! lock files, manifest files, `[tool.*]` tables and the build backend in pyproject.toml.
+//! Each file found is evidence for a package manager, and the package manager with the
+//! most evidence is used. A tie goes to the package manager with the strongest evidence, and
+//! then to the package manager that comes first in the `--manager` values.
+use std::{cmp::Reverse, fs, path::Path};
+
+use clap::ValueEnum;
+
+use crate::{domain::PackageManager, validators::PathError};
+
+/// A lock file is the strongest evidence, as it is written by the package manager itself
+const LOCK_FILE: u8 = 3;
+/// Configuration for a package manager, or a manifest file only it reads
+const PROJECT_FILE: u8 = 2;
+/// Build backends and requirements files are also used by projects managed by other tools
+const SHARED_FILE: u8 = 1;
+
+const PROJECT_FILES: [(&str, PackageManager, u8); 13] = [
+    ("poetry.lock", PackageManager::Poetry, LOCK_FILE),
+    ("uv.lock", PackageManager::Uv, LOCK_FILE),
+    ("pdm.lock", PackageManager::Pdm, LOCK_FILE),
+    ("Pipfile.lock", PackageManager::Pipenv, LOCK_FILE),
+    ("Pipfile", PackageManager::Pipenv, PROJECT_FILE),
+    ("hatch.toml", PackageManager::Hatch, PROJECT_FILE),
+    ("setup.py", PackageManager::Setuptools, PROJECT_FILE),
+    ("setup.cfg", PackageManager::Setuptools, PROJECT_FILE),
+    ("environment.yml", PackageManager::Conda, PROJECT_FILE),
+    ("environment.yaml", PackageManager::Conda, PROJECT_FILE),
+    ("requirements.in", PackageManager::Pip, PROJECT_FILE),
+    ("requirements.txt", PackageManager::Pip, SHARED_FILE),
+    ("requirements-dev.txt", PackageManager::Pip, SHARED_FILE),
+];
+
+/// The `[tool.*]` tables of pyproject.toml, as a path of keys. The hatchling build backend
+/// is configured in `[tool.hatch.build]` so only Hatch environments are evidence of Hatch
+const TOOL_TABLES: [(&[&str], PackageManager); 4] = [
+    (&["poetry"], PackageManager::Poetry),
+    (&["uv"], PackageManager::Uv),
+    (&["pdm"], PackageManager::Pdm),
+    (&["hatch", "envs"], PackageManager::Hatch),
+];
+
+const BUILD_BACKENDS: [(&str, PackageManager); 6] = [
+    ("poetry.core.masonry.api", PackageManager::Poetry),
+    ("uv_build", PackageManager::Uv),
+    ("pdm.backend", PackageManager::Pdm),
+    ("pdm.pep517.api", PackageManager::Pdm),
+    ("hatchling.build", PackageManager::Hatch),
+    ("setuptools.build_meta", PackageManager::Setuptools),
+];
+
+/// A project file, or part of pyproject.toml, that indicates a package manager is used
+#[derive(Debug, PartialEq)]
+struct Evidence {
+    pkg_manager: PackageManager,
+    source: String,
+    weight: u8,
+}
+
+/// Detects the package manager used by the project. When project files of several package
+/// managers are found, a warning explains which package manager was chosen and why
+pub fn detect_package_manager(source_code_path: &Path) -> Result<PackageManager, PathError> {
+    let evidence = find_evidence(source_code_path);
+    let mut candidates: Vec<(PackageManager, Rank)> = PackageManager::value_variants()
+        .iter()
+        .map(|pkg_manager| (*pkg_manager, rank(&evidence, *pkg_manager)))
+        .filter(|(_, rank)| rank.score > 0)
+        .collect();
+    // a stable sort keeps the enum order between package managers with the same rank
+    candidates.sort_by_key(|(_, rank)| Reverse(*rank));
+    let (pkg_manager, best) = *candidates.first().ok_or(PathError::NonSupportedTooling)?;
+
+    if candidates.len() > 1 {
+        let found: Vec<String> = candidates
+            .iter()
+            .map(|(candidate, _)| format!("{} ({})", candidate, sources(&evidence, *candidate)))
+            .collect();
+        eprintln!(
+            "WARNING: Found project files for several package managers: {}.",
+            found.join(", ")
+        );
+        let runner_up = candidates[1].1;
+        let reason = if best.score > runner_up.score {
+            "it has the most evidence"
+        } else if best.strongest > runner_up.strongest {
+            "its evidence is the strongest"
+        } else {
+            "the evidence is tied and it comes first in the --manager values"
+        };
+        eprintln!(
+            "Using {} as {}, pass --manager to choose another.",
+            pkg_manager, reason
+        );
+    }
+
+    Ok(pkg_manager)
+}
+
+/// How much evidence there is for a package manager. The total weight of the evidence is
+/// compared first, then the weight of the strongest single piece of evidence, so a manifest
+/// outweighs the requirements files that often accompany it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Rank {
+    score: u8,
+    strongest: u8,
+}
+
+fn rank(evidence: &[Evidence], pkg_manager: PackageManager) -> Rank {
+    Rank {
+        score: score(evidence, pkg_manager),
+        strongest: evidence
+            .iter()
+            .filter(|e| e.pkg_manager == pkg_manager)
+            .map(|e| e.weight)
+            .max()
+            .unwrap_or_default(),
+    }
+}
+
+fn score(evidence: &[Evidence], pkg_manager: PackageManager) -> u8 {
+    evidence
+        .iter()
+        .filter(|e| e.pkg_manager == pkg_manager)
+        .map(|e| e.weight)
+        .sum()
+}
+
+fn sources(evidence: &[Evidence], pkg_manager: PackageManager) -> String {
+    evidence
+        .iter()
+        .filter(|e| e.pkg_manager == pkg_manager)
+        .map(|e| e.source.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn find_evidence(source_code_path: &Path) -> Vec<Evidence> {
+    let mut evidence: Vec<Evidence> = PROJECT_FILES
+        .iter()
+        .filter(|(file_name, _, _)| fs::metadata(source_code_path.join(file_name)).is_ok())
+        .map(|(file_name, pkg_manager, weight)| Evidence {
+            pkg_manager: *pkg_manager,
+            source: file_name.to_string(),
+            weight: *weight,
+        })
+        .collect();
+
+    let Ok(toml_content) = fs::read_to_string(source_code_path.join("pyproject.toml")) else {
+        return evidence;
+    };
+    let Ok(pyproject) = toml_content.parse::<toml::Table>() else {
+        return evidence;
+    };
+
+    for (keys, pkg_manager) in TOOL_TABLES {
+        let mut table = pyproject.get("tool");
+        for key in keys {
+            table = table.and_then(|t| t.get(key));
+        }
+        if table.is_some() {
+            evidence.push(Evidence {
+                pkg_manager,
+                source: format!("[tool.{}]", keys.join(".")),
+                weight: PROJECT_FILE,
+            });
+        }
+    }
+
+    let build_backend = pyproject
+        .get("build-system")
+        .and_then(|build_system| build_system.get("build-backend"))
+        .and_then(|build_backend| build_backend.as_str());
+    if let Some(build_backend) = build_backend {
+        for (backend, pkg_manager) in BUILD_BACKENDS {
+            if build_backend == backend {
+                evidence.push(Evidence {
+                    pkg_manager,
+                    source: format!("build-backend {}", backend),
+                    weight: SHARED_FILE,
+                });
+            }
+        }
+    }
+
+    evidence
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_package_manager() {
+        let poetry_project = Path::new("tests/fixtures/example_project");
+        let pkg_manager = detect_package_manager(poetry_project);
+        assert_eq!(pkg_manager.ok(), Some(PackageManager::Poetry));
+
+        let uv_project = Path::new("tests/fixtures/example_uv_project");
+        let pkg_manager = detect_package_manager(uv_project);
+        assert_eq!(pkg_manager.ok(), Some(PackageManager::Uv));
+
+        let pdm_project = Path::new("tests/fixtures/example_pdm_project");
+        let pkg_manager = detect_package_manager(pdm_project);
+        assert_eq!(pkg_manager.ok(), Some(PackageManager::Pdm));
+
+        let pip_project = Path::new("tests/fixtures/example_requirements_project");
+        let pkg_manager = detect_package_manager(pip_project);
+        assert_eq!(pkg_manager.ok(), Some(PackageManager::Pip));
+
+        let pipenv_project = Path::new("tests/fixtures/example_pipenv_project");
+        let pkg_manager = detect_package_manager(pipenv_project);
+        assert_eq!(pkg_manager.ok(), Some(PackageManager::Pipenv));
+
+        let setuptools_project = Path::new("tests/fixtures/example_setuptools_project");
+        let pkg_manager = detect_package_manager(setuptools_project);
+        assert_eq!(pkg_manager.ok(), Some(PackageManager::Setuptools));
+
+        let conda_project = Path::new("tests/fixtures/example_conda_project");
+        let pkg_manager = detect_package_manager(conda_project);
+        assert_eq!(pkg_manager.ok(), Some(PackageManager::Conda));
+
+        let hatch_project = Path::new("tests/fixtures/example_hatch_project");
+        let pkg_manager = detect_package_manager(hatch_project);
+        assert_eq!(pkg_manager.ok(), Some(PackageManager::Hatch));
+    }
+
+    #[test]
+    fn test_detect_package_manager_from_build_backend() {
+        let project = Path::new("tests/fixtures/input/build_backend");
+        let pkg_manager = detect_package_manager(project);
+        assert_eq!(pkg_manager.ok(), Some(PackageManager::Pdm));
+    }
+
+    #[test]
+    fn test_detect_package_manager_with_several_package_managers() {
+        let project = Path::new("tests/fixtures/input/several_managers");
+        let evidence = find_evidence(project);
+        assert_eq!(score(&evidence, PackageManager::Poetry), 6);
+        assert_eq!(score(&evidence, PackageManager::Uv), 3);
+        assert_eq!(
+            sources(&evidence, PackageManager::Poetry),
+            "poetry.lock, [tool.poetry], build-backend poetry.core.masonry.api"
+        );
+
+        let pkg_manager = detect_package_manager(project);
+        assert_eq!(pkg_manager.ok(), Some(PackageManager::Poetry));
+    }
+
+    #[test]
+    fn test_detect_package_manager_prefers_manifest_over_requirements_files() {
+        let project = Path::new("tests/fixtures/input/setup_py_requirements");
+        let evidence = find_evidence(project);
+        assert_eq!(score(&evidence, PackageManager::Setuptools), 2);
+        assert_eq!(score(&evidence, PackageManager::Pip), 2);
+
+        let pkg_manager = detect_package_manager(project);
+        assert_eq!(pkg_manager.ok(), Some(PackageManager::Setuptools));
+    }
+
+    #[test]
+    fn test_detect_package_manager_non_supported_tooling() {
+        let project = Path::new("tests/fixtures/input/non_poetry");
+        let pkg_manager = detect_package_manager(project);
+        assert!(matches!(pkg_manager, Err(PathError::NonSupportedTooling)));
+    }
+}
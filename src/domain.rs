@@ -1,25 +1,175 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
     error::Error,
-    path::PathBuf,
+    fmt,
+    path::{Path, PathBuf},
 };
 
+use clap::ValueEnum;
+use serde::Serialize;
+
 use crate::{
-    formatters::reformat_package_name,
-    poetry::{check_lock_file_for_package_extras, get_dependencies_from_pyproject},
-    python_ast::get_imports_from_src,
+    conda::{get_dependencies_from_environment, ENVIRONMENT_FILES},
+    formatters::{normalize_package_name, reformat_package_name},
+    hatch,
+    package_imports::get_import_names,
+    pdm, pipenv, poetry,
+    python_ast::{
+        get_imports_from_src, top_level_module, ImportContext, ImportLocation, ImportRecord,
+        SourceImports, SourceSelection,
+    },
+    requirements::get_dependencies_from_requirements,
+    setuptools::get_dependencies_from_setuptools,
+    test_paths::TestPaths,
+    uv, venv,
 };
 
+/// The group of the main, or production, dependencies of a project
+pub const MAIN_GROUP: &str = "main";
+
+/// The group of the dev dependencies, for package managers without named dependency groups
+pub const DEV_GROUP: &str = "dev";
+
+/// Annotates a package installed as an extra of a (possibly) unused parent package
+const EXTRA_ANNOTATION: &str = " - an extra of ";
+
 /// PackageManager is an enum that represents the Python package manager used in the project this
 /// application should scan for unused packages.
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
 pub enum PackageManager {
     Poetry,
+    Uv,
+    Pdm,
+    Pip,
+    Pipenv,
+    Setuptools,
+    Conda,
+    Hatch,
+}
+
+impl fmt::Display for PackageManager {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PackageManager::Poetry => f.write_str("Poetry"),
+            PackageManager::Uv => f.write_str("uv"),
+            PackageManager::Pdm => f.write_str("PDM"),
+            PackageManager::Pip => f.write_str("pip"),
+            PackageManager::Pipenv => f.write_str("Pipenv"),
+            PackageManager::Setuptools => f.write_str("setuptools"),
+            PackageManager::Conda => f.write_str("Conda"),
+            PackageManager::Hatch => f.write_str("Hatch"),
+        }
+    }
+}
+
+/// The dependency groups to track besides the main group. Dev tracks the groups that are
+/// installed by default, while optional groups are only tracked when selected by name or
+/// with all groups
+#[derive(Clone, Debug, Default)]
+pub struct GroupSelection {
+    pub dev: bool,
+    pub names: Vec<String>,
+    pub all: bool,
+}
+
+impl GroupSelection {
+    /// Selects every dependency group, including optional groups
+    pub fn all_groups() -> Self {
+        Self {
+            all: true,
+            ..Default::default()
+        }
+    }
+
+    /// Whether dev dependencies are tracked, for package managers without dependency groups
+    pub fn with_dev(&self) -> bool {
+        self.dev || self.all
+    }
+
+    /// Warns about the groups selected by name that are not declared, where the declared
+    /// groups are the dependencies of every group keyed by group
+    fn warn_missing_groups(&self, declared: &HashMap<String, HashSet<String>>) {
+        for name in self.names.iter() {
+            if !declared.contains_key(name) {
+                eprintln!("WARNING: Dependency group `{}` not found.", name);
+            }
+        }
+    }
+
+    /// Whether the dependencies of a group are tracked
+    pub fn includes(&self, group: &str, optional: bool) -> bool {
+        group == MAIN_GROUP
+            || self.all
+            || self.names.iter().any(|name| name == group)
+            || (self.dev && !optional)
+    }
+}
+
+/// A declared package that is not imported, as reported per group
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct UnusedPackage {
+    pub package: String,
+    /// The (possibly) unused parent package, when the package is installed as its extra
+    pub extra_of: Option<String>,
+}
+
+impl fmt::Display for UnusedPackage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.extra_of {
+            Some(parent) => write!(f, "{}{}{}", self.package, EXTRA_ANNOTATION, parent),
+            None => f.write_str(&self.package),
+        }
+    }
+}
+
+/// A module imported in the source code that is not declared in the manifest, but is installed
+/// as a dependency of a declared package
+#[derive(Debug, PartialEq, Serialize)]
+pub struct TransitivePackage {
+    /// The chain of locked packages from the declared package to the imported package,
+    /// e.g. `["pydantic", "typing-extensions"]`
+    pub chain: Vec<String>,
+    /// Where the module is imported
+    pub locations: BTreeSet<ImportLocation>,
+}
+
+/// A package declared in the wrong group for where it is imported
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "kind", content = "locations", rename_all = "snake_case")]
+pub enum MisplacedPackage {
+    /// A package only declared in dev groups, with where production code imports it
+    DevImportedInProduction(BTreeSet<ImportLocation>),
+    /// A main package, with where test code, the only code to import it, imports it
+    MainImportedOnlyInTests(BTreeSet<ImportLocation>),
+}
+
+/// A main package that is never imported unconditionally at runtime
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LimitedUsage {
+    /// Only imported under `if TYPE_CHECKING:`, so it could be a dev or typing dependency
+    TypeCheckingOnly,
+    /// Only imported in a `try` block catching ImportError, so the code runs without it
+    OptionalOnly,
 }
 
 pub struct PythonProject {
     manifest_packages: HashSet<String>,
+    /// The groups each manifest package is declared in, for package managers with
+    /// dependency groups
+    package_groups: HashMap<String, BTreeSet<String>>,
+    /// The groups of every package declared in the manifest, whichever groups are tracked
+    declared_groups: HashMap<String, BTreeSet<String>>,
     extra_packages: HashMap<String, Vec<String>>,
-    import_statements: HashSet<String>,
+    /// The dependencies of each locked package, for package managers whose lock file has them
+    dependency_graph: HashMap<String, Vec<String>>,
+    /// The top level modules of each distribution installed in the project's virtualenv
+    installed_imports: HashMap<String, Vec<String>>,
+    /// The import names of packages declared in `[tool.deptr.module-map]`
+    module_map: HashMap<String, Vec<String>>,
+    /// The packages and modules left out of every report, keyed by their normalized name
+    ignored_packages: BTreeMap<String, String>,
+    import_statements: SourceImports,
 }
 
 impl PythonProject {
@@ -28,47 +178,590 @@ impl PythonProject {
         pkg_manager: PackageManager,
         project_path: PathBuf,
         verbose: bool,
-        dev: bool,
+        groups: GroupSelection,
+        sources: &SourceSelection,
     ) -> Result<Self, Box<dyn Error>> {
-        match pkg_manager {
-            PackageManager::Poetry => match Self::new_poetry(project_path, verbose, dev) {
+        let dev = groups.with_dev();
+        let venv_path = venv::find_project_venv(&project_path);
+        let mut import_statements = get_imports_from_src(&project_path, sources)?;
+        import_statements
+            .add_first_party_modules(poetry::get_packages_from_pyproject(&project_path)?);
+        let mut project = match pkg_manager {
+            PackageManager::Poetry => match Self::new_poetry(project_path, verbose, &groups) {
                 Ok(result) => Ok(result),
                 Err(e) => Err(e),
             },
+            PackageManager::Uv => Self::new_uv(project_path, verbose, &groups),
+            PackageManager::Pdm => Self::new_pdm(project_path, verbose, &groups),
+            PackageManager::Pip => Self::new_pip(project_path, dev),
+            PackageManager::Pipenv => Self::new_pipenv(project_path, verbose, dev),
+            PackageManager::Setuptools => Self::new_setuptools(project_path, dev),
+            PackageManager::Conda => Self::new_conda(project_path),
+            PackageManager::Hatch => Self::new_hatch(project_path, &groups),
+        }?;
+        project.import_statements = import_statements;
+
+        match venv_path {
+            Some(venv_path) => project.with_venv(&venv_path),
+            None => Ok(project),
         }
     }
 
-    fn new_poetry(project_path: PathBuf, verbose: bool, dev: bool) -> Result<Self, Box<dyn Error>> {
+    /// Uses the metadata of the distributions installed in the virtualenv to match packages
+    /// with imports, in place of the import name database
+    pub fn with_venv(mut self, venv_path: &Path) -> Result<Self, Box<dyn Error>> {
+        self.installed_imports = venv::get_installed_imports(venv_path)?;
+        Ok(self)
+    }
+
+    /// Matches the packages with the import names of the module map, keyed by the package name,
+    /// in place of the installed metadata and the import name database
+    pub fn with_module_map(mut self, module_map: HashMap<String, Vec<String>>) -> Self {
+        self.module_map = module_map
+            .into_iter()
+            .map(|(package, imports)| (normalize_package_name(&package), imports))
+            .collect();
+        self
+    }
+
+    /// Leaves the packages, and modules, out of every report
+    pub fn with_ignored_packages(mut self, packages: &[String]) -> Self {
+        self.ignored_packages = packages
+            .iter()
+            .map(|pkg| (normalize_package_name(pkg), pkg.clone()))
+            .collect();
+        self
+    }
+
+    fn new_poetry(
+        project_path: PathBuf,
+        verbose: bool,
+        groups: &GroupSelection,
+    ) -> Result<Self, Box<dyn Error>> {
         let toml_path = project_path.join("pyproject.toml");
-        let manifest_packages = get_dependencies_from_pyproject(&toml_path, dev)?;
+        let package_groups =
+            group_packages(poetry::get_dependencies_from_pyproject(&toml_path, groups)?);
+        let manifest_packages = package_groups.keys().cloned().collect();
+        let all_groups =
+            poetry::get_dependencies_from_pyproject(&toml_path, &GroupSelection::all_groups())?;
+        groups.warn_missing_groups(&all_groups);
+        let declared_groups = group_packages(all_groups);
         let extra_packages =
-            check_lock_file_for_package_extras(&project_path, &manifest_packages, verbose)?;
-        let import_statements = get_imports_from_src(&project_path)?;
+            poetry::check_lock_file_for_package_extras(&project_path, &manifest_packages, verbose)?;
+        let dependency_graph = poetry::get_dependency_graph_from_lock_file(&project_path)?;
 
         Ok(Self {
             manifest_packages,
+            declared_groups,
+            package_groups,
             extra_packages,
-            import_statements,
+            dependency_graph,
+            installed_imports: HashMap::new(),
+            module_map: HashMap::new(),
+            ignored_packages: BTreeMap::new(),
+            import_statements: SourceImports::default(),
+        })
+    }
+
+    fn new_uv(
+        project_path: PathBuf,
+        verbose: bool,
+        groups: &GroupSelection,
+    ) -> Result<Self, Box<dyn Error>> {
+        let toml_path = project_path.join("pyproject.toml");
+        let package_groups =
+            group_packages(uv::get_dependencies_from_pyproject(&toml_path, groups)?);
+        let manifest_packages = package_groups.keys().cloned().collect();
+        let all_groups =
+            uv::get_dependencies_from_pyproject(&toml_path, &GroupSelection::all_groups())?;
+        groups.warn_missing_groups(&all_groups);
+        let declared_groups = group_packages(all_groups);
+        let extra_packages =
+            uv::check_lock_file_for_package_extras(&project_path, &manifest_packages, verbose)?;
+
+        Ok(Self {
+            manifest_packages,
+            declared_groups,
+            package_groups,
+            extra_packages,
+            dependency_graph: HashMap::new(),
+            installed_imports: HashMap::new(),
+            module_map: HashMap::new(),
+            ignored_packages: BTreeMap::new(),
+            import_statements: SourceImports::default(),
+        })
+    }
+
+    fn new_pdm(
+        project_path: PathBuf,
+        verbose: bool,
+        groups: &GroupSelection,
+    ) -> Result<Self, Box<dyn Error>> {
+        let toml_path = project_path.join("pyproject.toml");
+        let package_groups =
+            group_packages(pdm::get_dependencies_from_pyproject(&toml_path, groups)?);
+        let manifest_packages = package_groups.keys().cloned().collect();
+        let all_groups =
+            pdm::get_dependencies_from_pyproject(&toml_path, &GroupSelection::all_groups())?;
+        groups.warn_missing_groups(&all_groups);
+        let declared_groups = group_packages(all_groups);
+        let extra_packages =
+            pdm::check_lock_file_for_package_extras(&project_path, &manifest_packages, verbose)?;
+
+        Ok(Self {
+            manifest_packages,
+            declared_groups,
+            package_groups,
+            extra_packages,
+            dependency_graph: HashMap::new(),
+            installed_imports: HashMap::new(),
+            module_map: HashMap::new(),
+            ignored_packages: BTreeMap::new(),
+            import_statements: SourceImports::default(),
+        })
+    }
+
+    /// pip requirements files have no lock file, so package extras are not known
+    fn new_pip(project_path: PathBuf, dev: bool) -> Result<Self, Box<dyn Error>> {
+        let main_packages = get_dependencies_from_requirements(&project_path, false)?;
+        let all_packages = get_dependencies_from_requirements(&project_path, true)?;
+        let package_groups = main_and_dev_groups(&main_packages, &all_packages);
+        let manifest_packages = if dev { all_packages } else { main_packages };
+
+        Ok(Self {
+            manifest_packages,
+            declared_groups: package_groups.clone(),
+            package_groups,
+            extra_packages: HashMap::new(),
+            dependency_graph: HashMap::new(),
+            installed_imports: HashMap::new(),
+            module_map: HashMap::new(),
+            ignored_packages: BTreeMap::new(),
+            import_statements: SourceImports::default(),
+        })
+    }
+
+    fn new_pipenv(project_path: PathBuf, verbose: bool, dev: bool) -> Result<Self, Box<dyn Error>> {
+        let pipfile_path = project_path.join("Pipfile");
+        let main_packages = pipenv::get_dependencies_from_pipfile(&pipfile_path, false)?;
+        let all_packages = pipenv::get_dependencies_from_pipfile(&pipfile_path, true)?;
+        let package_groups = main_and_dev_groups(&main_packages, &all_packages);
+        let manifest_packages = if dev { all_packages } else { main_packages };
+        let extra_packages =
+            pipenv::check_lock_file_for_package_extras(&project_path, &manifest_packages, verbose)?;
+
+        Ok(Self {
+            manifest_packages,
+            declared_groups: package_groups.clone(),
+            package_groups,
+            extra_packages,
+            dependency_graph: HashMap::new(),
+            installed_imports: HashMap::new(),
+            module_map: HashMap::new(),
+            ignored_packages: BTreeMap::new(),
+            import_statements: SourceImports::default(),
+        })
+    }
+
+    /// setuptools projects have no lock file, so package extras are not known
+    fn new_setuptools(project_path: PathBuf, dev: bool) -> Result<Self, Box<dyn Error>> {
+        let main_packages = get_dependencies_from_setuptools(&project_path, false)?;
+        let all_packages = get_dependencies_from_setuptools(&project_path, true)?;
+        let package_groups = main_and_dev_groups(&main_packages, &all_packages);
+        let manifest_packages = if dev { all_packages } else { main_packages };
+
+        Ok(Self {
+            manifest_packages,
+            declared_groups: package_groups.clone(),
+            package_groups,
+            extra_packages: HashMap::new(),
+            dependency_graph: HashMap::new(),
+            installed_imports: HashMap::new(),
+            module_map: HashMap::new(),
+            ignored_packages: BTreeMap::new(),
+            import_statements: SourceImports::default(),
+        })
+    }
+
+    /// Conda has no lock file or dev dependencies, so package extras are not known
+    fn new_conda(project_path: PathBuf) -> Result<Self, Box<dyn Error>> {
+        let environment_path = ENVIRONMENT_FILES
+            .iter()
+            .map(|file_name| project_path.join(file_name))
+            .find(|file_path| file_path.is_file())
+            .ok_or("Unable to find an environment.yml file")?;
+        let manifest_packages = get_dependencies_from_environment(&environment_path)?;
+
+        Ok(Self {
+            declared_groups: main_and_dev_groups(&manifest_packages, &manifest_packages),
+            manifest_packages,
+            package_groups: HashMap::new(),
+            extra_packages: HashMap::new(),
+            dependency_graph: HashMap::new(),
+            installed_imports: HashMap::new(),
+            module_map: HashMap::new(),
+            ignored_packages: BTreeMap::new(),
+            import_statements: SourceImports::default(),
+        })
+    }
+
+    /// Hatch projects have no lock file, so package extras are not known
+    fn new_hatch(project_path: PathBuf, groups: &GroupSelection) -> Result<Self, Box<dyn Error>> {
+        let toml_path = project_path.join("pyproject.toml");
+        let package_groups =
+            group_packages(hatch::get_dependencies_from_pyproject(&toml_path, groups)?);
+        let manifest_packages = package_groups.keys().cloned().collect();
+        let all_groups =
+            hatch::get_dependencies_from_pyproject(&toml_path, &GroupSelection::all_groups())?;
+        groups.warn_missing_groups(&all_groups);
+        let declared_groups = group_packages(all_groups);
+
+        Ok(Self {
+            manifest_packages,
+            declared_groups,
+            package_groups,
+            extra_packages: HashMap::new(),
+            dependency_graph: HashMap::new(),
+            installed_imports: HashMap::new(),
+            module_map: HashMap::new(),
+            ignored_packages: BTreeMap::new(),
+            import_statements: SourceImports::default(),
         })
     }
 
     /// Returns a HashSet of unused packages
     pub fn get_unused_packages(&self) -> HashSet<String> {
+        self.find_unused_packages()
+            .into_iter()
+            .filter(|unused| !self.is_unused_ignored(unused))
+            .collect()
+    }
+
+    /// Returns the unused packages keyed by the group they are declared in. A package declared
+    /// in several groups is returned in each of them, and packages of projects without
+    /// dependency groups are in the main group
+    pub fn get_unused_packages_by_group(&self) -> BTreeMap<String, BTreeSet<UnusedPackage>> {
+        let package_groups: HashMap<String, &BTreeSet<String>> = self
+            .package_groups
+            .iter()
+            .map(|(pkg, groups)| (reformat_package_name(pkg), groups))
+            .collect();
+
+        let mut result: BTreeMap<String, BTreeSet<UnusedPackage>> = BTreeMap::new();
+        for unused in self.get_unused_packages() {
+            let (package, extra_of) = match unused.split_once(EXTRA_ANNOTATION) {
+                Some((extra, parent)) => (extra, Some(parent)),
+                None => (unused.as_str(), None),
+            };
+            let main_group = BTreeSet::from([MAIN_GROUP.to_string()]);
+            let groups = package_groups
+                .get(&reformat_package_name(package))
+                .copied()
+                .unwrap_or(&main_group);
+            for group in groups.iter() {
+                result
+                    .entry(group.clone())
+                    .or_default()
+                    .insert(UnusedPackage {
+                        package: package.to_string(),
+                        extra_of: extra_of.map(str::to_string),
+                    });
+            }
+        }
+
+        result
+    }
+
+    /// Returns the third-party modules imported in the source code that are not declared in the
+    /// manifest, in any group, with where they are imported. Modules installed as a
+    /// dependency of a declared package are returned by `get_transitive_packages` instead
+    pub fn get_missing_packages(&self) -> BTreeMap<String, BTreeSet<ImportLocation>> {
+        self.find_undeclared_imports()
+            .into_iter()
+            .filter(|(module, _)| !self.is_ignored(module))
+            .filter(|(_, records)| self.find_dependency_chain(records).is_none())
+            .map(|(module, records)| (module.clone(), locations(&records)))
+            .collect()
+    }
+
+    /// Returns the third-party modules imported in the source code that are not declared in the
+    /// manifest but are locked as a dependency of a declared package. These only work because
+    /// of the declared package and should be declared directly
+    pub fn get_transitive_packages(&self) -> BTreeMap<String, TransitivePackage> {
+        self.find_undeclared_imports()
+            .into_iter()
+            .filter(|(module, _)| !self.is_ignored(module))
+            .filter_map(|(module, records)| {
+                let chain = self.find_dependency_chain(&records)?;
+                let locations = locations(&records);
+                Some((module.clone(), TransitivePackage { chain, locations }))
+            })
+            .collect()
+    }
+
+    /// Returns the declared packages whose group does not match where they are imported: dev
+    /// packages imported from production code, which are not installed in production, and main
+    /// packages only imported from tests, which could be dev packages
+    pub fn get_misplaced_packages(
+        &self,
+        test_paths: &TestPaths,
+    ) -> BTreeMap<String, MisplacedPackage> {
+        self.find_misplaced_packages(test_paths)
+            .into_iter()
+            .filter(|(pkg, _)| !self.is_ignored(pkg))
+            .collect()
+    }
+
+    /// Returns the main packages that are only imported for type checking, or only imported
+    /// optionally. Packages imported in any other context, e.g. at runtime or on some Python
+    /// versions, are needed in production
+    pub fn get_limited_usage_packages(&self) -> BTreeMap<String, LimitedUsage> {
+        self.find_limited_usage_packages()
+            .into_iter()
+            .filter(|(pkg, _)| !self.is_ignored(pkg))
+            .collect()
+    }
+
+    /// Returns the packages with a limited usage, ignored or not
+    fn find_limited_usage_packages(&self) -> BTreeMap<String, LimitedUsage> {
+        let mut result = BTreeMap::new();
+        for (pkg, groups) in self.declared_groups.iter() {
+            if !groups.contains(MAIN_GROUP) {
+                continue;
+            }
+            let contexts: BTreeSet<ImportContext> = self
+                .import_records(pkg)
+                .iter()
+                .map(|record| record.context)
+                .collect();
+
+            let usage = match contexts.first() {
+                Some(ImportContext::TypeChecking) => LimitedUsage::TypeCheckingOnly,
+                Some(ImportContext::Optional) => LimitedUsage::OptionalOnly,
+                _ => continue,
+            };
+            result.insert(pkg.clone(), usage);
+        }
+
+        result
+    }
+
+    /// Returns the ignored packages, and modules, that are in none of the reports, so the
+    /// ignore has no effect and can be removed. Packages only declared in groups that are not
+    /// tracked are never stale, as they are not checked
+    pub fn get_stale_ignores(&self, test_paths: &TestPaths) -> BTreeSet<String> {
+        let mut reported: HashSet<String> = HashSet::new();
+        for unused in self.find_unused_packages() {
+            let pkg = unused_package_name(&unused);
+            reported.insert(normalize_package_name(pkg));
+            reported.extend(
+                self.declared_names(pkg)
+                    .map(|pkg| normalize_package_name(pkg)),
+            );
+        }
+        reported.extend(
+            self.find_undeclared_imports()
+                .into_keys()
+                .map(|module| normalize_package_name(module)),
+        );
+        reported.extend(
+            self.find_misplaced_packages(test_paths)
+                .keys()
+                .chain(self.find_limited_usage_packages().keys())
+                .map(|pkg| normalize_package_name(pkg)),
+        );
+        let untracked: HashSet<String> = self
+            .declared_groups
+            .keys()
+            .filter(|pkg| !self.manifest_packages.contains(*pkg))
+            .map(|pkg| normalize_package_name(pkg))
+            .collect();
+
+        self.ignored_packages
+            .iter()
+            .filter(|(normalized, _)| {
+                !reported.contains(*normalized) && !untracked.contains(*normalized)
+            })
+            .map(|(_, pkg)| pkg.clone())
+            .collect()
+    }
+
+    /// Returns the misplaced packages, ignored or not
+    fn find_misplaced_packages(
+        &self,
+        test_paths: &TestPaths,
+    ) -> BTreeMap<String, MisplacedPackage> {
+        let mut result: BTreeMap<String, MisplacedPackage> = BTreeMap::new();
+        for (pkg, groups) in self.declared_groups.iter() {
+            let records = self.import_records(pkg);
+            if records.is_empty() {
+                continue;
+            }
+            let (test_imports, production_imports): (BTreeSet<_>, BTreeSet<_>) = records
+                .into_iter()
+                .map(ImportRecord::location)
+                .partition(|location| test_paths.is_test_file(&location.file));
+
+            if groups.contains(MAIN_GROUP) {
+                if production_imports.is_empty() {
+                    let misplaced = MisplacedPackage::MainImportedOnlyInTests(test_imports);
+                    result.insert(pkg.clone(), misplaced);
+                }
+            } else if !production_imports.is_empty() {
+                let misplaced = MisplacedPackage::DevImportedInProduction(production_imports);
+                result.insert(pkg.clone(), misplaced);
+            }
+        }
+
+        result
+    }
+
+    /// Returns the imports that are not of a package declared in the manifest, keyed by their
+    /// top level package
+    fn find_undeclared_imports(&self) -> BTreeMap<&String, Vec<&ImportRecord>> {
+        let declared_imports: HashSet<String> = self
+            .declared_groups
+            .keys()
+            .flat_map(|pkg| self.import_names(pkg))
+            .collect();
+
+        self.import_statements
+            .imports
+            .iter()
+            .filter_map(|(module, records)| {
+                let undeclared: Vec<&ImportRecord> = records
+                    .iter()
+                    .filter(|record| {
+                        !declared_imports
+                            .iter()
+                            .any(|import| record.imports_module(import))
+                    })
+                    .collect();
+                (!undeclared.is_empty()).then_some((module, undeclared))
+            })
+            .collect()
+    }
+
+    /// Finds the shortest chain of locked packages from a declared package to a package that
+    /// installs the imported modules, searching the declared packages in name order
+    fn find_dependency_chain(&self, records: &[&ImportRecord]) -> Option<Vec<String>> {
+        let targets: HashSet<&str> = self
+            .dependency_graph
+            .keys()
+            .filter(|pkg| {
+                self.import_names(pkg)
+                    .iter()
+                    .any(|import| records.iter().any(|record| record.imports_module(import)))
+            })
+            .map(String::as_str)
+            .collect();
+        if targets.is_empty() {
+            return None;
+        }
+
+        let roots: BTreeSet<String> = self
+            .declared_groups
+            .keys()
+            .map(|pkg| normalize_package_name(pkg))
+            .collect();
+        let mut parents: HashMap<&str, &str> = HashMap::new();
+        let mut queue: VecDeque<&str> = VecDeque::new();
+        for root in roots.iter() {
+            if let Some((root, _)) = self.dependency_graph.get_key_value(root) {
+                parents.insert(root, root);
+                queue.push_back(root);
+            }
+        }
+
+        while let Some(package) = queue.pop_front() {
+            if targets.contains(package) {
+                let mut chain = vec![package.to_string()];
+                let mut current = package;
+                while parents[current] != current {
+                    current = parents[current];
+                    chain.push(current.to_string());
+                }
+                chain.reverse();
+                // a chain of one package is a declared package, not a transitive dependency
+                return (chain.len() > 1).then_some(chain);
+            }
+            let dependencies = self.dependency_graph.get(package).into_iter().flatten();
+            for dependency in dependencies {
+                if !parents.contains_key(dependency.as_str()) {
+                    parents.insert(dependency, package);
+                    queue.push_back(dependency);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Returns the imports of a package, matched by its import names
+    fn import_records(&self, pkg: &str) -> Vec<&ImportRecord> {
+        self.import_names(pkg)
+            .iter()
+            .flat_map(|import| {
+                self.import_statements
+                    .imports
+                    .get(top_level_module(import))
+                    .into_iter()
+                    .flatten()
+                    .filter(move |record| record.imports_module(import))
+            })
+            .collect()
+    }
+
+    /// Returns the import names of a package, from `[tool.deptr.module-map]` when the
+    /// package is mapped, then from the virtualenv when the package is installed, and otherwise
+    /// from the import name database
+    fn import_names(&self, pkg: &str) -> Vec<String> {
+        let normalized = normalize_package_name(pkg);
+        match self
+            .module_map
+            .get(&normalized)
+            .or_else(|| self.installed_imports.get(&normalized))
+        {
+            Some(imports) => imports.clone(),
+            None => get_import_names(pkg),
+        }
+    }
+
+    /// Whether the package, or module, is left out of the reports
+    fn is_ignored(&self, name: &str) -> bool {
+        self.ignored_packages
+            .contains_key(&normalize_package_name(name))
+    }
+
+    /// Whether the unused package is left out of the reports, by its reported name, e.g.
+    /// `dotenv`, or by the name it is declared with in the manifest, e.g. `python-dotenv`
+    fn is_unused_ignored(&self, unused: &str) -> bool {
+        let pkg = unused_package_name(unused);
+        self.is_ignored(pkg) || self.declared_names(pkg).any(|pkg| self.is_ignored(pkg))
+    }
+
+    /// Returns the names the manifest declares the reported package with, as the reported name
+    /// is reformatted to match the imports
+    fn declared_names<'a>(&'a self, reported: &str) -> impl Iterator<Item = &'a String> {
+        let reported = normalize_package_name(&reformat_package_name(reported));
+        self.declared_groups
+            .keys()
+            .filter(move |pkg| normalize_package_name(&reformat_package_name(pkg)) == reported)
+    }
+
+    /// Returns the unused packages, ignored or not
+    fn find_unused_packages(&self) -> HashSet<String> {
         let unused_packages = self.find_unused_manifest_packages();
         self.filter_package_extras(unused_packages)
     }
 
-    /// Returns a HashSet of unused packages from the manifest
+    /// Returns a HashSet of unused packages from the manifest, that are not imported by any of
+    /// their import names
     fn find_unused_manifest_packages(&self) -> HashSet<String> {
-        let manifest_packages_fmt: HashSet<String> = self
-            .manifest_packages
+        self.manifest_packages
             .iter()
+            .filter(|pkg| self.import_records(pkg).is_empty())
             .map(|pkg| reformat_package_name(pkg))
-            .collect();
-
-        manifest_packages_fmt
-            .difference(&self.import_statements)
-            .cloned()
             .collect()
     }
 
@@ -76,14 +769,25 @@ impl PythonProject {
     fn filter_package_extras(&self, unused_packages: HashSet<String>) -> HashSet<String> {
         let mut result = unused_packages.clone();
 
+        // The lock file names, e.g. `sentry-sdk`, are compared with the reformatted names of the
+        // unused packages, e.g. `sentry_sdk`, by their normalized names
+        let unused_names: HashMap<String, &String> = unused_packages
+            .iter()
+            .map(|unused| (normalize_package_name(unused), unused))
+            .collect();
+        let find_unused = |pkg: &str| {
+            unused_names
+                .get(&normalize_package_name(&reformat_package_name(pkg)))
+                .copied()
+        };
+
         // If a package is installed as an extra of a parent package,
         // and the parent package is used, then the extra package is removed
         // from the unused_packages
         for (pkg, extras) in self.extra_packages.iter() {
             for extra in extras.iter() {
-                let extra_pkg = reformat_package_name(extra);
-                if !unused_packages.contains(pkg) && unused_packages.contains(&extra_pkg) {
-                    result.remove(&extra_pkg);
+                if let (None, Some(extra_pkg)) = (find_unused(pkg), find_unused(extra)) {
+                    result.remove(extra_pkg);
                 }
             }
         }
@@ -93,10 +797,9 @@ impl PythonProject {
         // annotated as an extra package of the (possibly) unused parent package
         for (pkg, extras) in self.extra_packages.iter() {
             for extra in extras.iter() {
-                let extra_pkg = reformat_package_name(extra);
-                if unused_packages.contains(pkg) && unused_packages.contains(&extra_pkg) {
-                    result.remove(&extra_pkg);
-                    let annotated_pkg = format!("{} - an extra of {}", extra, pkg);
+                if let (Some(_), Some(extra_pkg)) = (find_unused(pkg), find_unused(extra)) {
+                    result.remove(extra_pkg);
+                    let annotated_pkg = format!("{}{}{}", extra, EXTRA_ANNOTATION, pkg);
                     result.insert(annotated_pkg);
                 }
             }
@@ -106,17 +809,82 @@ impl PythonProject {
     }
 }
 
+/// Returns where the records are, sorted by file, line and column
+fn locations(records: &[&ImportRecord]) -> BTreeSet<ImportLocation> {
+    records.iter().map(|record| record.location()).collect()
+}
+
+/// Returns the package name of an unused package, without the annotation of an extra
+fn unused_package_name(unused: &str) -> &str {
+    unused
+        .split_once(EXTRA_ANNOTATION)
+        .map_or(unused, |(extra, _)| extra)
+}
+
+/// Returns the groups keyed by package for package managers that only tell main and dev
+/// dependencies apart, where the packages that are not main dependencies are in the dev group
+fn main_and_dev_groups(
+    main_packages: &HashSet<String>,
+    all_packages: &HashSet<String>,
+) -> HashMap<String, BTreeSet<String>> {
+    all_packages
+        .iter()
+        .map(|pkg| {
+            let group = if main_packages.contains(pkg) {
+                MAIN_GROUP
+            } else {
+                DEV_GROUP
+            };
+            (pkg.clone(), BTreeSet::from([group.to_string()]))
+        })
+        .collect()
+}
+
+/// Inverts the dependencies keyed by group into the groups keyed by package
+fn group_packages(groups: HashMap<String, HashSet<String>>) -> HashMap<String, BTreeSet<String>> {
+    let mut package_groups: HashMap<String, BTreeSet<String>> = HashMap::new();
+    for (group, packages) in groups {
+        for pkg in packages {
+            package_groups.entry(pkg).or_default().insert(group.clone());
+        }
+    }
+    package_groups
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// The location of an import statement at the start of a line
+    fn location(file: &str, line: usize) -> ImportLocation {
+        ImportLocation {
+            file: PathBuf::from(file),
+            line,
+            column: 1,
+        }
+    }
+
+    /// An unused package that is not an extra
+    fn unused(package: &str) -> UnusedPackage {
+        UnusedPackage {
+            package: package.to_string(),
+            extra_of: None,
+        }
+    }
+
     #[test]
     fn test_get_unused_packages() {
         let project_path: PathBuf = PathBuf::from("tests/fixtures/example_project");
         let verbose = false;
-        let dev = false;
+        let groups = GroupSelection::default();
         let pkg_manager = PackageManager::Poetry;
-        let project = PythonProject::new(pkg_manager, project_path, verbose, dev);
+        let project = PythonProject::new(
+            pkg_manager,
+            project_path,
+            verbose,
+            groups,
+            &SourceSelection::default(),
+        );
         let result = project
             .expect("expected to get unused deps")
             .get_unused_packages();
@@ -133,9 +901,15 @@ mod tests {
     fn test_find_unused_manifest_packages() {
         let project_path: PathBuf = PathBuf::from("tests/fixtures/example_project");
         let verbose = false;
-        let dev = false;
+        let groups = GroupSelection::default();
         let pkg_manager = PackageManager::Poetry;
-        let project = PythonProject::new(pkg_manager, project_path, verbose, dev);
+        let project = PythonProject::new(
+            pkg_manager,
+            project_path,
+            verbose,
+            groups,
+            &SourceSelection::default(),
+        );
         let result = project
             .expect("expected to get unused deps")
             .find_unused_manifest_packages();
@@ -154,9 +928,15 @@ mod tests {
     fn test_filter_package_extras() {
         let project_path: PathBuf = PathBuf::from("tests/fixtures/example_project");
         let verbose = false;
-        let dev = false;
+        let groups = GroupSelection::default();
         let pkg_manager = PackageManager::Poetry;
-        let project = PythonProject::new(pkg_manager, project_path, verbose, dev);
+        let project = PythonProject::new(
+            pkg_manager,
+            project_path,
+            verbose,
+            groups,
+            &SourceSelection::default(),
+        );
         let unused_packages = project
             .as_ref()
             .expect("expected to get unused deps")
@@ -177,9 +957,15 @@ mod tests {
     fn test_filter_package_extras_returns_annotated_package() {
         let project_path: PathBuf = PathBuf::from("tests/fixtures/example_project_2");
         let verbose = false;
-        let dev = false;
+        let groups = GroupSelection::default();
         let pkg_manager = PackageManager::Poetry;
-        let project = PythonProject::new(pkg_manager, project_path, verbose, dev);
+        let project = PythonProject::new(
+            pkg_manager,
+            project_path,
+            verbose,
+            groups,
+            &SourceSelection::default(),
+        );
         let unused_packages = project
             .as_ref()
             .expect("expected to get unused deps")
@@ -194,4 +980,677 @@ mod tests {
         ];
         assert_eq!(result, expected.iter().cloned().collect());
     }
+
+    #[test]
+    fn test_get_unused_packages_uv_project() {
+        let project_path: PathBuf = PathBuf::from("tests/fixtures/example_uv_project");
+        let verbose = false;
+        let groups = GroupSelection::default();
+        let pkg_manager = PackageManager::Uv;
+        let project = PythonProject::new(
+            pkg_manager,
+            project_path,
+            verbose,
+            groups,
+            &SourceSelection::default(),
+        );
+        let result = project
+            .expect("expected to get unused deps")
+            .get_unused_packages();
+        let expected = [
+            "redis".to_string(),
+            "sentry_sdk".to_string(),
+            "pydantic".to_string(),
+            "email-validator - an extra of pydantic".to_string(),
+        ];
+        assert_eq!(result, expected.iter().cloned().collect());
+    }
+
+    #[test]
+    fn test_get_unused_packages_pdm_project() {
+        let project_path: PathBuf = PathBuf::from("tests/fixtures/example_pdm_project");
+        let verbose = false;
+        let groups = GroupSelection::default();
+        let pkg_manager = PackageManager::Pdm;
+        let project = PythonProject::new(
+            pkg_manager,
+            project_path,
+            verbose,
+            groups,
+            &SourceSelection::default(),
+        );
+        let result = project
+            .expect("expected to get unused deps")
+            .get_unused_packages();
+        let expected = [
+            "pydantic".to_string(),
+            "email-validator - an extra of pydantic".to_string(),
+        ];
+        assert_eq!(result, expected.iter().cloned().collect());
+    }
+
+    #[test]
+    fn test_get_unused_packages_pip_project() {
+        let project_path: PathBuf = PathBuf::from("tests/fixtures/example_requirements_project");
+        let verbose = false;
+        let groups = GroupSelection::default();
+        let pkg_manager = PackageManager::Pip;
+        let project = PythonProject::new(
+            pkg_manager,
+            project_path,
+            verbose,
+            groups,
+            &SourceSelection::default(),
+        );
+        let result = project
+            .expect("expected to get unused deps")
+            .get_unused_packages();
+        let expected = [
+            "pywin32".to_string(),
+            "sentry_sdk".to_string(),
+            "redis".to_string(),
+        ];
+        assert_eq!(result, expected.iter().cloned().collect());
+    }
+
+    #[test]
+    fn test_get_unused_packages_pipenv_project() {
+        let project_path: PathBuf = PathBuf::from("tests/fixtures/example_pipenv_project");
+        let verbose = false;
+        let groups = GroupSelection::default();
+        let pkg_manager = PackageManager::Pipenv;
+        let project = PythonProject::new(
+            pkg_manager,
+            project_path,
+            verbose,
+            groups,
+            &SourceSelection::default(),
+        );
+        let result = project
+            .expect("expected to get unused deps")
+            .get_unused_packages();
+        let expected = [
+            "redis".to_string(),
+            "sentry_sdk".to_string(),
+            "pydantic".to_string(),
+            "email_validator".to_string(),
+        ];
+        assert_eq!(result, expected.iter().cloned().collect());
+    }
+
+    #[test]
+    fn test_get_unused_packages_pipenv_project_with_extras() {
+        let project_path: PathBuf = PathBuf::from("tests/fixtures/input/pipenv_extras");
+        let verbose = false;
+        let groups = GroupSelection::default();
+        let pkg_manager = PackageManager::Pipenv;
+        let project = PythonProject::new(
+            pkg_manager,
+            project_path,
+            verbose,
+            groups,
+            &SourceSelection::default(),
+        );
+        let result = project
+            .expect("expected to get unused deps")
+            .get_unused_packages();
+        // email-validator shares a prefix with the `email` extra of the used pydantic, but is
+        // not the extra, so it is still unused
+        let expected = [
+            "sentry_sdk".to_string(),
+            "celery - an extra of sentry-sdk".to_string(),
+            "email_validator".to_string(),
+        ];
+        assert_eq!(result, expected.iter().cloned().collect());
+    }
+
+    #[test]
+    fn test_get_unused_packages_setuptools_project() {
+        let project_path: PathBuf = PathBuf::from("tests/fixtures/example_setuptools_project");
+        let verbose = false;
+        let groups = GroupSelection::default();
+        let pkg_manager = PackageManager::Setuptools;
+        let project = PythonProject::new(
+            pkg_manager,
+            project_path,
+            verbose,
+            groups,
+            &SourceSelection::default(),
+        );
+        let result = project
+            .expect("expected to get unused deps")
+            .get_unused_packages();
+        let expected = [
+            "pywin32".to_string(),
+            "tenacity".to_string(),
+            "redis".to_string(),
+            "PyYAML".to_string(),
+        ];
+        assert_eq!(result, expected.iter().cloned().collect());
+    }
+
+    #[test]
+    fn test_get_unused_packages_conda_project() {
+        let project_path: PathBuf = PathBuf::from("tests/fixtures/example_conda_project");
+        let verbose = false;
+        let groups = GroupSelection::default();
+        let pkg_manager = PackageManager::Conda;
+        let project = PythonProject::new(
+            pkg_manager,
+            project_path,
+            verbose,
+            groups,
+            &SourceSelection::default(),
+        );
+        let result = project
+            .expect("expected to get unused deps")
+            .get_unused_packages();
+        let expected = [
+            "scipy".to_string(),
+            "matplotlib_base".to_string(),
+            "wandb".to_string(),
+            "lightgbm".to_string(),
+        ];
+        assert_eq!(result, expected.iter().cloned().collect());
+    }
+
+    #[test]
+    fn test_get_unused_packages_with_import_names() {
+        let project_path: PathBuf = PathBuf::from("tests/fixtures/input/import_names");
+        let verbose = false;
+        let groups = GroupSelection::default();
+        let pkg_manager = PackageManager::Pip;
+        let project = PythonProject::new(
+            pkg_manager,
+            project_path,
+            verbose,
+            groups,
+            &SourceSelection::default(),
+        )
+        .expect("valid project");
+        // protobuf installs `google.protobuf`, which the `google.cloud` import is not
+        assert_eq!(
+            project.get_unused_packages(),
+            HashSet::from(["requests".to_string(), "protobuf".to_string()])
+        );
+        assert!(project.get_missing_packages().is_empty());
+    }
+
+    #[test]
+    fn test_get_unused_packages_with_module_map() {
+        let project_path: PathBuf = PathBuf::from("tests/fixtures/input/module_map");
+        let module_map = crate::config::load_config(&project_path)
+            .expect("valid config")
+            .module_map;
+        let verbose = false;
+        let groups = GroupSelection::default();
+        let pkg_manager = PackageManager::Poetry;
+        let project = PythonProject::new(
+            pkg_manager,
+            project_path,
+            verbose,
+            groups,
+            &SourceSelection::default(),
+        )
+        .expect("valid project")
+        .with_module_map(module_map);
+        assert_eq!(
+            project.get_unused_packages(),
+            HashSet::from(["acme_unused_client".to_string()])
+        );
+        assert!(project.get_missing_packages().is_empty());
+    }
+
+    #[test]
+    fn test_get_unused_packages_with_venv() {
+        let project_path: PathBuf = PathBuf::from("tests/fixtures/input/venv_project");
+        let verbose = false;
+        let groups = GroupSelection::default();
+        let pkg_manager = PackageManager::Pip;
+        let project = PythonProject::new(
+            pkg_manager,
+            project_path,
+            verbose,
+            groups,
+            &SourceSelection::default(),
+        )
+        .expect("valid project");
+        assert_eq!(
+            project.get_unused_packages(),
+            HashSet::from(["six".to_string()])
+        );
+        assert!(project.get_missing_packages().is_empty());
+    }
+
+    #[test]
+    fn test_get_unused_packages_with_venv_namespace_packages() {
+        let project_path: PathBuf = PathBuf::from("tests/fixtures/input/venv_namespace_packages");
+        let verbose = false;
+        let groups = GroupSelection::default();
+        let pkg_manager = PackageManager::Pip;
+        let project = PythonProject::new(
+            pkg_manager,
+            project_path,
+            verbose,
+            groups,
+            &SourceSelection::default(),
+        )
+        .expect("valid project");
+        // the `google.cloud` import is of google-cloud-storage only, not of every distribution
+        // installed in the `google` namespace
+        assert_eq!(
+            project.get_unused_packages(),
+            HashSet::from(["protobuf".to_string(), "google_api_core".to_string()])
+        );
+        assert!(project.get_missing_packages().is_empty());
+    }
+
+    #[test]
+    fn test_get_unused_packages_with_first_party_modules() {
+        let project_path: PathBuf = PathBuf::from("tests/fixtures/input/first_party");
+        let verbose = false;
+        let groups = GroupSelection::default();
+        let pkg_manager = PackageManager::Poetry;
+        let project = PythonProject::new(
+            pkg_manager,
+            project_path,
+            verbose,
+            groups,
+            &SourceSelection::default(),
+        )
+        .expect("valid project");
+        // `scripts/requests.py` does not hide the imports of the requests dependency, while the
+        // `acme.markdown` module is not an import of the markdown dependency
+        assert_eq!(
+            project.get_unused_packages(),
+            HashSet::from(["markdown".to_string()])
+        );
+        assert!(project.get_missing_packages().is_empty());
+    }
+
+    #[test]
+    fn test_get_unused_packages_by_group_hatch_project() {
+        let project_path: PathBuf = PathBuf::from("tests/fixtures/example_hatch_project");
+        let verbose = false;
+        let groups = GroupSelection {
+            dev: true,
+            ..Default::default()
+        };
+        let pkg_manager = PackageManager::Hatch;
+        let project = PythonProject::new(
+            pkg_manager,
+            project_path,
+            verbose,
+            groups,
+            &SourceSelection::default(),
+        );
+        let result = project
+            .expect("expected to get unused deps")
+            .get_unused_packages_by_group();
+        let expected = BTreeMap::from([
+            (MAIN_GROUP.to_string(), BTreeSet::from([unused("redis")])),
+            (
+                "default".to_string(),
+                BTreeSet::from([unused("pytest_cov")]),
+            ),
+            (
+                "lint".to_string(),
+                BTreeSet::from([unused("ruff"), unused("mypy")]),
+            ),
+            (
+                "docs".to_string(),
+                BTreeSet::from([unused("mkdocs_material")]),
+            ),
+        ]);
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_get_unused_packages_by_group_poetry_project() {
+        let project_path: PathBuf = PathBuf::from("tests/fixtures/input/poetry_groups");
+        let verbose = false;
+        let groups = GroupSelection {
+            dev: true,
+            ..Default::default()
+        };
+        let pkg_manager = PackageManager::Poetry;
+        let project = PythonProject::new(
+            pkg_manager,
+            project_path,
+            verbose,
+            groups,
+            &SourceSelection::default(),
+        );
+        let result = project
+            .expect("expected to get unused deps")
+            .get_unused_packages_by_group();
+        let expected = BTreeMap::from([
+            (MAIN_GROUP.to_string(), BTreeSet::from([unused("redis")])),
+            (
+                "dev".to_string(),
+                BTreeSet::from([unused("ipython"), unused("pre_commit")]),
+            ),
+            ("lint".to_string(), BTreeSet::from([unused("ruff")])),
+            ("test".to_string(), BTreeSet::from([unused("pytest_cov")])),
+        ]);
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_get_unused_packages_by_group_dependency_groups() {
+        let project_path: PathBuf = PathBuf::from("tests/fixtures/input/dependency_groups");
+        let verbose = false;
+        let groups = GroupSelection {
+            names: vec!["dev".to_string()],
+            ..Default::default()
+        };
+        let pkg_manager = PackageManager::Uv;
+        let project = PythonProject::new(
+            pkg_manager,
+            project_path,
+            verbose,
+            groups,
+            &SourceSelection::default(),
+        );
+        let result = project
+            .expect("expected to get unused deps")
+            .get_unused_packages_by_group();
+        let expected = BTreeMap::from([(
+            "dev".to_string(),
+            BTreeSet::from([unused("ipython"), unused("mypy"), unused("pytest_cov")]),
+        )]);
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_get_missing_packages() {
+        let project_path: PathBuf = PathBuf::from("tests/fixtures/input/missing_imports");
+        let verbose = false;
+        let groups = GroupSelection::default();
+        let pkg_manager = PackageManager::Pip;
+        let project = PythonProject::new(
+            pkg_manager,
+            project_path,
+            verbose,
+            groups,
+            &SourceSelection::default(),
+        );
+        let result = project
+            .expect("expected to get missing deps")
+            .get_missing_packages();
+        let expected = BTreeMap::from([
+            (
+                "urllib3".to_string(),
+                BTreeSet::from([location("app/client.py", 3)]),
+            ),
+            (
+                "yaml".to_string(),
+                BTreeSet::from([location("app/client.py", 2), location("app/settings.py", 3)]),
+            ),
+        ]);
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_get_missing_packages_with_ignored_packages() {
+        let project_path: PathBuf = PathBuf::from("tests/fixtures/input/missing_imports");
+        let verbose = false;
+        let groups = GroupSelection::default();
+        let pkg_manager = PackageManager::Pip;
+        let project = PythonProject::new(
+            pkg_manager,
+            project_path,
+            verbose,
+            groups,
+            &SourceSelection::default(),
+        )
+        .expect("valid project")
+        .with_ignored_packages(&["YAML".to_string()]);
+        assert_eq!(
+            project.get_missing_packages().keys().collect::<Vec<_>>(),
+            vec!["urllib3"]
+        );
+    }
+
+    #[test]
+    fn test_get_unused_packages_with_ignored_packages() {
+        let project_path: PathBuf = PathBuf::from("tests/fixtures/example_project");
+        let verbose = false;
+        let groups = GroupSelection::default();
+        let pkg_manager = PackageManager::Poetry;
+        let project = PythonProject::new(
+            pkg_manager,
+            project_path,
+            verbose,
+            groups,
+            &SourceSelection::default(),
+        )
+        .expect("valid project")
+        .with_ignored_packages(&["sentry-sdk".to_string(), "redis".to_string()]);
+        let result = project.get_unused_packages();
+        assert!(!result.contains("sentry_sdk"));
+        assert!(!result.contains("redis"));
+        assert!(result.contains("tenacity"));
+    }
+
+    #[test]
+    fn test_get_unused_packages_with_ignored_declared_name() {
+        let project_path: PathBuf = PathBuf::from("tests/fixtures/input/pep621");
+        let verbose = false;
+        let groups = GroupSelection::default();
+        let pkg_manager = PackageManager::Poetry;
+        let project = PythonProject::new(
+            pkg_manager,
+            project_path,
+            verbose,
+            groups,
+            &SourceSelection::default(),
+        )
+        .expect("valid project");
+        assert!(project.get_unused_packages().contains("dotenv"));
+
+        // the package is reported as `dotenv`, but ignored as declared in the manifest
+        let project = project.with_ignored_packages(&["python-dotenv".to_string()]);
+        assert!(!project.get_unused_packages().contains("dotenv"));
+        assert!(project.get_stale_ignores(&TestPaths::default()).is_empty());
+    }
+
+    #[test]
+    fn test_get_stale_ignores() {
+        let project_path: PathBuf = PathBuf::from("tests/fixtures/example_project");
+        let verbose = false;
+        let groups = GroupSelection::default();
+        let pkg_manager = PackageManager::Poetry;
+        let project = PythonProject::new(
+            pkg_manager,
+            project_path,
+            verbose,
+            groups,
+            &SourceSelection::default(),
+        )
+        .expect("valid project")
+        .with_ignored_packages(&[
+            "Redis".to_string(),
+            "requests".to_string(),
+            "flask".to_string(),
+            "pytest".to_string(),
+        ]);
+        assert_eq!(
+            project.get_stale_ignores(&TestPaths::default()),
+            BTreeSet::from(["flask".to_string(), "requests".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_get_limited_usage_packages() {
+        let project_path: PathBuf = PathBuf::from("tests/fixtures/input/import_contexts");
+        let verbose = false;
+        let groups = GroupSelection::default();
+        let pkg_manager = PackageManager::Pip;
+        let project = PythonProject::new(
+            pkg_manager,
+            project_path,
+            verbose,
+            groups,
+            &SourceSelection::default(),
+        )
+        .expect("valid project");
+        let expected = BTreeMap::from([
+            ("mypy-boto3-s3".to_string(), LimitedUsage::TypeCheckingOnly),
+            ("numpy".to_string(), LimitedUsage::TypeCheckingOnly),
+            ("orjson".to_string(), LimitedUsage::OptionalOnly),
+            ("ujson".to_string(), LimitedUsage::OptionalOnly),
+        ]);
+        assert_eq!(project.get_limited_usage_packages(), expected);
+    }
+
+    #[test]
+    fn test_get_transitive_packages() {
+        let project_path: PathBuf = PathBuf::from("tests/fixtures/input/transitive");
+        let verbose = false;
+        let groups = GroupSelection::default();
+        let pkg_manager = PackageManager::Poetry;
+        let project = PythonProject::new(
+            pkg_manager,
+            project_path,
+            verbose,
+            groups,
+            &SourceSelection::default(),
+        )
+        .expect("valid project");
+        let result = project.get_transitive_packages();
+        assert_eq!(result.len(), 3);
+        assert_eq!(
+            result.get("sniffio"),
+            Some(&TransitivePackage {
+                chain: vec![
+                    "fastapi".to_string(),
+                    "anyio".to_string(),
+                    "sniffio".to_string()
+                ],
+                locations: BTreeSet::from([location("app/main.py", 1)]),
+            })
+        );
+        assert_eq!(
+            result.get("typing_extensions").map(|t| &t.chain),
+            Some(&vec![
+                "fastapi".to_string(),
+                "typing-extensions".to_string()
+            ])
+        );
+
+        let missing = project.get_missing_packages();
+        assert_eq!(missing.keys().collect::<Vec<_>>(), vec!["yaml"]);
+    }
+
+    #[test]
+    fn test_get_misplaced_packages() {
+        let project_path: PathBuf = PathBuf::from("tests/fixtures/input/misplaced");
+        let verbose = false;
+        let groups = GroupSelection::default();
+        let pkg_manager = PackageManager::Poetry;
+        let project = PythonProject::new(
+            pkg_manager,
+            project_path,
+            verbose,
+            groups,
+            &SourceSelection::default(),
+        );
+        let result = project
+            .expect("expected to get misplaced deps")
+            .get_misplaced_packages(&TestPaths::default());
+        let expected = BTreeMap::from([
+            (
+                "freezegun".to_string(),
+                MisplacedPackage::MainImportedOnlyInTests(BTreeSet::from([
+                    location("conftest.py", 1),
+                    location("tests/test_cli.py", 2),
+                ])),
+            ),
+            (
+                "rich".to_string(),
+                MisplacedPackage::DevImportedInProduction(BTreeSet::from([location(
+                    "app/cli.py",
+                    2,
+                )])),
+            ),
+        ]);
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_get_misplaced_packages_pip_project() {
+        let project_path: PathBuf = PathBuf::from("tests/fixtures/input/missing_imports");
+        let verbose = false;
+        let groups = GroupSelection::default();
+        let pkg_manager = PackageManager::Pip;
+        let project = PythonProject::new(
+            pkg_manager,
+            project_path,
+            verbose,
+            groups,
+            &SourceSelection::default(),
+        );
+        let result = project
+            .expect("expected to get misplaced deps")
+            .get_misplaced_packages(&TestPaths::new(vec!["app/".to_string()]));
+        assert_eq!(
+            result.get("requests"),
+            Some(&MisplacedPackage::MainImportedOnlyInTests(BTreeSet::from(
+                [location("app/client.py", 1)]
+            )))
+        );
+        assert_eq!(
+            result.get("pytest"),
+            Some(&MisplacedPackage::DevImportedInProduction(BTreeSet::from(
+                [location("tests/test_client.py", 1)]
+            )))
+        );
+    }
+
+    #[test]
+    fn test_group_selection_includes() {
+        let selection = GroupSelection::default();
+        assert!(selection.includes(MAIN_GROUP, false));
+        assert!(!selection.includes("test", false));
+
+        let selection = GroupSelection {
+            dev: true,
+            names: vec!["docs".to_string()],
+            all: false,
+        };
+        assert!(selection.includes("test", false));
+        assert!(selection.includes("docs", true));
+        assert!(!selection.includes("benchmark", true));
+    }
+
+    #[test]
+    fn test_get_unused_packages_by_group_without_groups() {
+        let project_path: PathBuf = PathBuf::from("tests/fixtures/example_project_2");
+        let verbose = false;
+        let groups = GroupSelection::default();
+        let pkg_manager = PackageManager::Poetry;
+        let project = PythonProject::new(
+            pkg_manager,
+            project_path,
+            verbose,
+            groups,
+            &SourceSelection::default(),
+        );
+        let result = project
+            .expect("expected to get unused deps")
+            .get_unused_packages_by_group();
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result.get(MAIN_GROUP),
+            Some(&BTreeSet::from([
+                unused("requests"),
+                unused("pydantic"),
+                UnusedPackage {
+                    package: "email-validator".to_string(),
+                    extra_of: Some("pydantic".to_string()),
+                },
+            ]))
+        );
+    }
 }
@@ -15,6 +15,17 @@ pub fn reformat_package_name(package: &str) -> String {
     package
 }
 
+/// Normalizes the package name as per PEP 503, so names written in a manifest can be compared
+/// with the names written in a lock file, e.g. `Email_Validator` becomes `email-validator`
+pub fn normalize_package_name(package: &str) -> String {
+    package
+        .split(['-', '_', '.'])
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_lowercase()
+}
+
 #[test]
 fn test_reformat_package_name() {
     let dep = "python-redis";
@@ -33,3 +44,11 @@ fn test_reformat_package_name() {
     let transformed_dep = reformat_package_name(dep);
     assert_eq!(transformed_dep, "redis");
 }
+
+#[test]
+fn test_normalize_package_name() {
+    assert_eq!(normalize_package_name("Email_Validator"), "email-validator");
+    assert_eq!(normalize_package_name("zope.interface"), "zope-interface");
+    assert_eq!(normalize_package_name("sentry--sdk"), "sentry-sdk");
+    assert_eq!(normalize_package_name("redis"), "redis");
+}
@@ -0,0 +1,142 @@
+//! Hatch is a Python project manager, where the standard `[project]` table declares the
+//! package dependencies and each Hatch environment declares its own additional dependencies.
+//! This module parses the pyproject.toml and hatch.toml files to get package dependencies.
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fs,
+    path::Path,
+    string::String,
+};
+
+extern crate toml;
+use serde::Deserialize;
+
+use crate::{domain::GroupSelection, pep508::parse_requirement, pyproject::PyProjectToml};
+
+#[derive(Deserialize, Debug)]
+struct Tool {
+    hatch: Option<Hatch>,
+}
+
+/// The Hatch configuration, which is the `[tool.hatch]` table of pyproject.toml
+/// or the top level of hatch.toml
+#[derive(Deserialize, Debug, Default)]
+struct Hatch {
+    #[serde(default)]
+    envs: HashMap<String, Environment>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Environment {
+    #[serde(default)]
+    dependencies: Vec<String>,
+    #[serde(rename = "extra-dependencies", default)]
+    extra_dependencies: Vec<String>,
+}
+
+/// Reads the pyproject.toml file, and the hatch.toml file next to it, and returns the
+/// dependencies keyed by group. The `[project]` dependencies are in the main group and,
+/// as the environments are used for development, each selected environment is a dev
+/// dependency group named after the environment, as are the selected `[dependency-groups]`
+pub fn get_dependencies_from_pyproject(
+    toml_file_path: &Path,
+    selection: &GroupSelection,
+) -> Result<HashMap<String, HashSet<String>>, Box<dyn Error>> {
+    let pyproject: PyProjectToml<Tool> = PyProjectToml::read(toml_file_path)?;
+    let mut groups = pyproject.get_dependencies(selection)?;
+
+    if selection.with_dev() || !selection.names.is_empty() {
+        let mut hatch = pyproject
+            .tool
+            .and_then(|tool| tool.hatch)
+            .unwrap_or_default();
+        let hatch_toml_path = toml_file_path
+            .parent()
+            .unwrap_or(Path::new("."))
+            .join("hatch.toml");
+        if hatch_toml_path.is_file() {
+            let hatch_toml: Hatch = toml::from_str(&fs::read_to_string(hatch_toml_path)?)?;
+            hatch.envs.extend(hatch_toml.envs);
+        }
+
+        for (name, environment) in hatch.envs.iter() {
+            if !selection.includes(name, false) {
+                continue;
+            }
+            let specs = environment
+                .dependencies
+                .iter()
+                .chain(environment.extra_dependencies.iter());
+            let mut dependencies = HashSet::new();
+            for spec in specs {
+                dependencies.insert(parse_requirement(spec)?.name);
+            }
+            groups.entry(name.clone()).or_default().extend(dependencies);
+        }
+    }
+
+    Ok(groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::MAIN_GROUP;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_get_dependencies_from_pyproject() {
+        let toml_file_path: PathBuf =
+            PathBuf::from("tests/fixtures/example_hatch_project/pyproject.toml");
+        let groups = get_dependencies_from_pyproject(&toml_file_path, &GroupSelection::default())
+            .expect("expected to get deps from manifest");
+        assert_eq!(groups.len(), 1);
+        assert_eq!(
+            groups.get(MAIN_GROUP),
+            Some(&["httpx".to_string(), "redis".to_string()].into())
+        );
+    }
+
+    #[test]
+    fn test_get_dependencies_from_pyproject_with_dev() {
+        let toml_file_path: PathBuf =
+            PathBuf::from("tests/fixtures/example_hatch_project/pyproject.toml");
+        let selection = GroupSelection {
+            dev: true,
+            ..Default::default()
+        };
+        let groups = get_dependencies_from_pyproject(&toml_file_path, &selection)
+            .expect("expected to get deps from manifest");
+        assert_eq!(groups.len(), 4);
+        assert_eq!(
+            groups.get("default"),
+            Some(&["pytest".to_string(), "pytest-cov".to_string()].into())
+        );
+        assert_eq!(
+            groups.get("lint"),
+            Some(&["ruff".to_string(), "mypy".to_string()].into())
+        );
+        assert_eq!(
+            groups.get("docs"),
+            Some(&["mkdocs-material".to_string()].into())
+        );
+    }
+
+    #[test]
+    fn test_get_dependencies_from_pyproject_named_environment() {
+        let toml_file_path: PathBuf =
+            PathBuf::from("tests/fixtures/example_hatch_project/pyproject.toml");
+        let selection = GroupSelection {
+            names: vec!["docs".to_string()],
+            ..Default::default()
+        };
+        let groups = get_dependencies_from_pyproject(&toml_file_path, &selection)
+            .expect("expected to get deps from manifest");
+        assert_eq!(groups.len(), 2);
+        assert_eq!(
+            groups.get("docs"),
+            Some(&["mkdocs-material".to_string()].into())
+        );
+    }
+}
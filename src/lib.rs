@@ -1,16 +1,47 @@
+mod conda;
+mod config;
+mod detector;
 pub mod domain;
 mod formatters;
+mod hatch;
+mod package_imports;
+mod pdm;
+mod pep508;
+mod pep621;
+mod pep735;
+mod pipenv;
 mod poetry;
+mod pyproject;
 mod python_ast;
 mod python_std_lib;
+mod requirements;
+mod setuptools;
+mod test_paths;
+mod uv;
 pub mod validators;
+mod venv;
 
-use std::{error::Error, path::PathBuf, string::String, time::Instant};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    error::Error,
+    path::PathBuf,
+    string::String,
+    time::Instant,
+};
 
 extern crate clap;
 use clap::Parser;
+use serde::Serialize;
 
-use crate::domain::{PackageManager, PythonProject};
+use crate::{
+    config::{load_config, IgnoredPackage, OutputFormat},
+    domain::{
+        GroupSelection, LimitedUsage, MisplacedPackage, PackageManager, PythonProject,
+        TransitivePackage, UnusedPackage, MAIN_GROUP,
+    },
+    python_ast::{ImportLocation, SourceSelection},
+    test_paths::TestPaths,
+};
 
 type CliResult<T> = Result<T, Box<dyn Error>>;
 
@@ -20,10 +51,55 @@ struct Cli {
     /// Optional path to operate on. If not provided, uses current working directory
     path: Option<String>,
 
-    /// Track dev dependencies (default: false)
+    /// Package manager of the project. If not provided, it is detected from the project files
+    #[arg(short, long, value_enum)]
+    manager: Option<PackageManager>,
+
+    /// Track dev dependencies (default: false, or `dev` of the config)
     #[arg(short, long, default_value = "false")]
     dev: bool,
 
+    /// Do not track dev dependencies, even when `dev` of the config is true (default: false)
+    #[arg(long, default_value = "false", conflicts_with = "dev")]
+    no_dev: bool,
+
+    /// Track the dependencies of a dependency group, can be used more than once
+    #[arg(short, long = "group", value_name = "NAME")]
+    groups: Vec<String>,
+
+    /// Track the dependencies of all dependency groups, including optional groups (default: false)
+    #[arg(long, default_value = "false")]
+    all_groups: bool,
+
+    /// Package, or module, to leave out of every report, can be used more than once.
+    /// Added to the `ignore` of the config
+    #[arg(long, value_name = "PACKAGE")]
+    ignore: Vec<String>,
+
+    /// Path of the virtualenv to match packages with imports by their installed metadata.
+    /// If not provided, uses the .venv or venv directory of the project when there is one
+    #[arg(long, value_name = "PATH")]
+    venv: Option<PathBuf>,
+
+    /// Path pattern of test code, e.g. `tests/` or `*_test.py`, can be used more than once.
+    /// Replaces the default patterns: tests/, test/, conftest.py, test_*.py and *_test.py
+    #[arg(long = "test-path", value_name = "PATTERN")]
+    test_paths: Vec<String>,
+
+    /// Source root to scan for imports, relative to the path, can be used more than once.
+    /// Replaces the `src` of the config. If not provided, scans the whole project
+    #[arg(long = "src", value_name = "PATH")]
+    src: Vec<String>,
+
+    /// Directory to exclude from the scan for imports, by name or by path relative to the path,
+    /// can be used more than once. Added to the `exclude` of the config
+    #[arg(long, value_name = "DIR")]
+    exclude: Vec<String>,
+
+    /// Format of the results. If not provided, uses the `format` of the config (default: text)
+    #[arg(short, long, value_enum)]
+    format: Option<OutputFormat>,
+
     /// Times the execution of the command (default: false)
     #[arg(short, long, default_value = "false")]
     timer: bool,
@@ -37,16 +113,23 @@ struct Cli {
 #[derive(Debug)]
 pub struct Config {
     src_path: PathBuf,
-    dev: bool,
+    pkg_manager: PackageManager,
+    groups: GroupSelection,
+    sources: SourceSelection,
+    module_map: HashMap<String, Vec<String>>,
+    ignore: Vec<IgnoredPackage>,
+    test_paths: TestPaths,
+    venv: Option<PathBuf>,
+    format: OutputFormat,
     timer: bool,
     verbose: bool,
 }
 
-/// get_args parses the command line arguments and returns a Config struct
+/// get_args parses the command line arguments, merges them with the config of the project and
+/// returns a Config struct
 pub fn get_args() -> CliResult<Config> {
     let cli = Cli::parse();
     let path = cli.path.unwrap_or_else(|| ".".to_string());
-    let dev = cli.dev;
     let timer = cli.timer;
     let verbose = cli.verbose;
 
@@ -55,40 +138,235 @@ pub fn get_args() -> CliResult<Config> {
         Ok(valid_path) => valid_path,
         Err(e) => return Err(Box::new(e)),
     };
+    let file_config = load_config(&src_path)?;
+
+    let groups = GroupSelection {
+        dev: !cli.no_dev && (cli.dev || file_config.dev.unwrap_or(false)),
+        names: cli.groups,
+        all: cli.all_groups,
+    };
+    let roots = if cli.src.is_empty() {
+        file_config.src
+    } else {
+        cli.src
+    };
+    let sources = SourceSelection {
+        roots: roots.into_iter().map(PathBuf::from).collect(),
+        exclude: file_config.exclude.into_iter().chain(cli.exclude).collect(),
+    };
+    let test_paths = if cli.test_paths.is_empty() {
+        TestPaths::default()
+    } else {
+        TestPaths::new(cli.test_paths)
+    };
+    let pyproject_path = src_path.join("pyproject.toml");
+    let inline_ignores = if pyproject_path.is_file() {
+        poetry::get_inline_ignores_from_pyproject(&pyproject_path)?
+    } else {
+        Vec::new()
+    };
+    let ignore = file_config
+        .ignore
+        .into_iter()
+        .chain(inline_ignores)
+        .chain(cli.ignore.into_iter().map(IgnoredPackage::new))
+        .collect();
+    let format = cli.format.or(file_config.format).unwrap_or_default();
+    let pkg_manager = match cli.manager {
+        Some(pkg_manager) => pkg_manager,
+        None => detector::detect_package_manager(&src_path)?,
+    };
 
     Ok(Config {
         src_path,
-        dev,
+        pkg_manager,
+        groups,
+        sources,
+        module_map: file_config.module_map,
+        ignore,
+        test_paths,
+        venv: cli.venv,
+        format,
         timer,
         verbose,
     })
 }
 
+/// The results of a run, as printed in the JSON format
+#[derive(Serialize)]
+struct Report {
+    unused: BTreeMap<String, BTreeSet<UnusedPackage>>,
+    missing: BTreeMap<String, BTreeSet<ImportLocation>>,
+    transitive: BTreeMap<String, TransitivePackage>,
+    misplaced: BTreeMap<String, MisplacedPackage>,
+    limited_usage: BTreeMap<String, LimitedUsage>,
+    stale_ignores: BTreeSet<String>,
+}
+
 /// run executes the application
 pub fn run(config: Config) -> CliResult<()> {
     let start = Instant::now();
 
-    // NOTE: currently only supports poetry projects
-    let pkg_manager = PackageManager::Poetry;
-    let project = PythonProject::new(pkg_manager, config.src_path, config.verbose, config.dev)?;
-    let unused_packages = project.get_unused_packages();
+    let ignored: Vec<String> = config
+        .ignore
+        .iter()
+        .map(|ignored| ignored.package.clone())
+        .collect();
+    let mut project = PythonProject::new(
+        config.pkg_manager,
+        config.src_path,
+        config.verbose,
+        config.groups,
+        &config.sources,
+    )?
+    .with_module_map(config.module_map)
+    .with_ignored_packages(&ignored);
+    if let Some(venv_path) = config.venv {
+        project = project.with_venv(&venv_path)?;
+    }
+    let report = Report {
+        unused: project.get_unused_packages_by_group(),
+        missing: project.get_missing_packages(),
+        transitive: project.get_transitive_packages(),
+        misplaced: project.get_misplaced_packages(&config.test_paths),
+        limited_usage: project.get_limited_usage_packages(),
+        stale_ignores: project.get_stale_ignores(&config.test_paths),
+    };
+
+    if config.verbose {
+        for ignored in config.ignore.iter() {
+            let Some(reason) = &ignored.reason else {
+                continue;
+            };
+            if !report.stale_ignores.contains(&ignored.package) {
+                let message = format!("Ignoring {} - {}", ignored.package, reason);
+                match config.format {
+                    OutputFormat::Text => println!("{}", message),
+                    OutputFormat::Json => eprintln!("{}", message),
+                }
+            }
+        }
+    }
+
+    match config.format {
+        OutputFormat::Text => print_text(report),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+    }
+
+    if config.timer {
+        let duration = start.elapsed();
+        match config.format {
+            OutputFormat::Text => {
+                println!("======================================");
+                println!("Execution time: {:?}", duration);
+            }
+            // keeps the output valid JSON
+            OutputFormat::Json => eprintln!("Execution time: {:?}", duration),
+        }
+    }
+
+    Ok(())
+}
+
+fn print_text(report: Report) {
+    let Report {
+        unused: mut unused_packages,
+        missing: missing_packages,
+        transitive: transitive_packages,
+        misplaced: misplaced_packages,
+        limited_usage: limited_usage_packages,
+        stale_ignores,
+    } = report;
 
     if unused_packages.is_empty() {
         println!("======================================");
         println!("No unused packages found.");
-    } else {
+    }
+    if let Some(main_packages) = unused_packages.remove(MAIN_GROUP) {
         println!("======================================");
         println!("Possible unused manifest packages: ");
-        for dep in unused_packages.iter() {
+        for dep in main_packages.iter() {
+            println!("{}", dep);
+        }
+    }
+    for (group, packages) in unused_packages.iter() {
+        println!("======================================");
+        println!("Possible unused packages in `{}`: ", group);
+        for dep in packages.iter() {
             println!("{}", dep);
         }
     }
 
-    if config.timer {
-        let duration = start.elapsed();
+    if !missing_packages.is_empty() {
         println!("======================================");
-        println!("Execution time: {:?}", duration);
+        println!("Possible missing manifest packages: ");
+        for (module, locations) in missing_packages.iter() {
+            println!("{} - imported in {}", module, display_locations(locations));
+        }
     }
 
-    Ok(())
+    if !transitive_packages.is_empty() {
+        println!("======================================");
+        println!("Packages used transitively, these should be declared directly: ");
+        for (module, transitive) in transitive_packages.iter() {
+            println!(
+                "{} - installed by {}, imported in {}",
+                module,
+                transitive.chain.join(" -> "),
+                display_locations(&transitive.locations)
+            );
+        }
+    }
+
+    if !misplaced_packages.is_empty() {
+        println!("======================================");
+        println!("Possible misplaced packages: ");
+        for (pkg, misplaced) in misplaced_packages.iter() {
+            match misplaced {
+                MisplacedPackage::DevImportedInProduction(locations) => println!(
+                    "{} - a dev dependency imported in {}",
+                    pkg,
+                    display_locations(locations)
+                ),
+                MisplacedPackage::MainImportedOnlyInTests(locations) => println!(
+                    "{} - a main dependency only imported in {}",
+                    pkg,
+                    display_locations(locations)
+                ),
+            }
+        }
+    }
+
+    if !limited_usage_packages.is_empty() {
+        println!("======================================");
+        println!("Packages not imported at runtime: ");
+        for (pkg, usage) in limited_usage_packages.iter() {
+            match usage {
+                LimitedUsage::TypeCheckingOnly => println!(
+                    "{} - only imported for type checking, it could be a dev dependency",
+                    pkg
+                ),
+                LimitedUsage::OptionalOnly => println!(
+                    "{} - only imported optionally, the code runs without it",
+                    pkg
+                ),
+            }
+        }
+    }
+
+    if !stale_ignores.is_empty() {
+        println!("======================================");
+        println!("Stale ignores, these packages are not reported and can be removed: ");
+        for pkg in stale_ignores.iter() {
+            println!("{}", pkg);
+        }
+    }
+}
+
+/// Displays the locations as comma-separated `file:line`, one entry per line number. The
+/// locations are sorted, so the entries of imports on the same line are next to each other
+fn display_locations(locations: &BTreeSet<ImportLocation>) -> String {
+    let mut lines: Vec<String> = locations.iter().map(ImportLocation::to_string).collect();
+    lines.dedup();
+    lines.join(", ")
 }
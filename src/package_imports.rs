@@ -0,0 +1,186 @@
+//! A curated database of the top level import names of distributions whose import name cannot
+//! be derived from the distribution name, e.g. `PIL` for `pillow` or `yaml` for `pyyaml`. The
+//! distributions of a namespace package install a module of it, so their import name is the
+//! module path, e.g. `google.protobuf` for `protobuf`.
+use crate::formatters::{normalize_package_name, reformat_package_name};
+
+/// Distributions, by their normalized name, and their import names
+const PACKAGE_IMPORTS: [(&str, &[&str]); 75] = [
+    ("attrs", &["attr", "attrs"]),
+    ("beautifulsoup4", &["bs4"]),
+    ("discord-py", &["discord"]),
+    ("django-cors-headers", &["corsheaders"]),
+    ("django-environ", &["environ"]),
+    ("django-filter", &["django_filters"]),
+    ("django-redis", &["django_redis"]),
+    ("djangorestframework", &["rest_framework"]),
+    ("dnspython", &["dns"]),
+    ("docker-py", &["docker"]),
+    ("faiss-cpu", &["faiss"]),
+    ("faiss-gpu", &["faiss"]),
+    ("gitpython", &["git"]),
+    ("google-api-python-client", &["googleapiclient"]),
+    ("google-auth-httplib2", &["google_auth_httplib2"]),
+    ("google-auth-oauthlib", &["google_auth_oauthlib"]),
+    ("google-cloud-core", &["google.cloud"]),
+    (
+        "googleapis-common-protos",
+        &[
+            "google.api",
+            "google.longrunning",
+            "google.rpc",
+            "google.type",
+        ],
+    ),
+    ("grpcio", &["grpc"]),
+    ("grpcio-status", &["grpc_status"]),
+    ("ipython", &["IPython"]),
+    ("jack-client", &["jack"]),
+    ("libsass", &["sass"]),
+    ("llama-cpp-python", &["llama_cpp"]),
+    ("matplotlib-base", &["matplotlib", "mpl_toolkits", "pylab"]),
+    ("mkdocs-material", &["material"]),
+    ("msgpack-python", &["msgpack"]),
+    ("mysql-connector-python", &["mysql"]),
+    ("mysqlclient", &["MySQLdb"]),
+    ("opencv-contrib-python", &["cv2"]),
+    ("opencv-contrib-python-headless", &["cv2"]),
+    ("opencv-python", &["cv2"]),
+    ("opencv-python-headless", &["cv2"]),
+    ("opensearch-py", &["opensearchpy"]),
+    ("paho-mqtt", &["paho"]),
+    ("pillow", &["PIL"]),
+    ("protobuf", &["google.protobuf"]),
+    ("psycopg-binary", &["psycopg"]),
+    ("psycopg2-binary", &["psycopg2"]),
+    ("py-cpuinfo", &["cpuinfo"]),
+    ("pycairo", &["cairo"]),
+    ("pycryptodome", &["Crypto"]),
+    ("pycryptodomex", &["Cryptodome"]),
+    ("pygithub", &["github"]),
+    ("pygobject", &["gi"]),
+    ("pyhamcrest", &["hamcrest"]),
+    ("pyjwt", &["jwt"]),
+    ("pymongo", &["pymongo", "bson", "gridfs"]),
+    ("pymupdf", &["fitz", "pymupdf"]),
+    ("pyopenssl", &["OpenSSL"]),
+    ("pyqt5", &["PyQt5"]),
+    ("pyqt6", &["PyQt6"]),
+    ("pyserial", &["serial"]),
+    ("pyside2", &["PySide2"]),
+    ("pyside6", &["PySide6"]),
+    ("python-docx", &["docx"]),
+    ("python-jose", &["jose"]),
+    ("python-multipart", &["multipart", "python_multipart"]),
+    ("python-pptx", &["pptx"]),
+    ("python-slugify", &["slugify"]),
+    ("python-socketio", &["socketio"]),
+    ("python-telegram-bot", &["telegram"]),
+    ("pytorch", &["torch"]),
+    ("pyusb", &["usb"]),
+    (
+        "pywin32",
+        &["win32api", "win32con", "win32com", "win32gui", "pywintypes"],
+    ),
+    ("pyyaml", &["yaml"]),
+    ("pyzmq", &["zmq"]),
+    ("ruamel-yaml", &["ruamel"]),
+    ("scikit-image", &["skimage"]),
+    ("scikit-learn", &["sklearn"]),
+    ("setuptools", &["setuptools", "pkg_resources"]),
+    ("tensorflow-cpu", &["tensorflow"]),
+    ("tensorflow-gpu", &["tensorflow"]),
+    ("tensorflow-macos", &["tensorflow"]),
+    ("websocket-client", &["websocket"]),
+];
+
+/// Prefixes of distributions that install a module of a namespace package, e.g.
+/// `google-cloud-storage` installs `google.cloud.storage`
+const NAMESPACE_PREFIXES: [&str; 5] = ["google-", "azure-", "jaraco-", "sphinxcontrib-", "zope-"];
+
+/// Returns the import names of a distribution from the curated database, then the module path
+/// of a namespace package distribution, and otherwise derives the import name from the
+/// distribution name, e.g. `dotenv` for `python-dotenv` or `flask` for `Flask`
+pub fn get_import_names(package: &str) -> Vec<String> {
+    let normalized = normalize_package_name(package);
+    if let Some((_, imports)) = PACKAGE_IMPORTS.iter().find(|(pkg, _)| *pkg == normalized) {
+        return imports.iter().map(|import| import.to_string()).collect();
+    }
+    if NAMESPACE_PREFIXES
+        .iter()
+        .any(|prefix| normalized.starts_with(prefix))
+    {
+        return vec![normalized.replace('-', ".")];
+    }
+
+    let import = reformat_package_name(package);
+    let lowercase = import.to_lowercase();
+    if lowercase == import {
+        vec![import]
+    } else {
+        vec![import, lowercase]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_import_names() {
+        assert_eq!(get_import_names("Pillow"), vec!["PIL".to_string()]);
+        assert_eq!(get_import_names("PyYAML"), vec!["yaml".to_string()]);
+        assert_eq!(
+            get_import_names("scikit_learn"),
+            vec!["sklearn".to_string()]
+        );
+        assert_eq!(get_import_names("beautifulsoup4"), vec!["bs4".to_string()]);
+        assert_eq!(get_import_names("opencv-python"), vec!["cv2".to_string()]);
+        assert_eq!(
+            get_import_names("attrs"),
+            vec!["attr".to_string(), "attrs".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_get_import_names_of_namespace_packages() {
+        assert_eq!(
+            get_import_names("google-cloud-storage"),
+            vec!["google.cloud.storage".to_string()]
+        );
+        assert_eq!(
+            get_import_names("azure-identity"),
+            vec!["azure.identity".to_string()]
+        );
+        assert_eq!(
+            get_import_names("protobuf"),
+            vec!["google.protobuf".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_get_import_names_derived_from_package_name() {
+        assert_eq!(
+            get_import_names("python-dotenv"),
+            vec!["dotenv".to_string()]
+        );
+        assert_eq!(
+            get_import_names("sentry-sdk"),
+            vec!["sentry_sdk".to_string()]
+        );
+        assert_eq!(
+            get_import_names("Flask"),
+            vec!["Flask".to_string(), "flask".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_package_imports_are_normalized_and_unique() {
+        for (i, (pkg, _)) in PACKAGE_IMPORTS.iter().enumerate() {
+            assert_eq!(normalize_package_name(pkg), *pkg);
+            assert!(!PACKAGE_IMPORTS[i + 1..]
+                .iter()
+                .any(|(other, _)| other == pkg));
+        }
+    }
+}
@@ -0,0 +1,191 @@
+//! PDM is a Python package and dependency manager supporting the latest PEP standards.
+//! This module parses PDM pyproject.toml and lock files to get package dependencies.
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fs,
+    io::Read,
+    path::Path,
+    string::String,
+};
+
+extern crate toml;
+use serde::Deserialize;
+
+use crate::{
+    domain::GroupSelection, formatters::normalize_package_name, pep508::parse_requirement,
+    pyproject::PyProjectToml,
+};
+
+#[derive(Deserialize, Debug)]
+struct Tool {
+    pdm: Option<Pdm>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Pdm {
+    #[serde(rename = "dev-dependencies")]
+    dev_dependencies: Option<HashMap<String, Vec<String>>>,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+struct PdmLock {
+    #[serde(default)]
+    package: Vec<Package>,
+}
+
+/// A locked package. PDM locks each combination of a package and its requested extras
+/// as a separate entry, whose dependencies are the packages installed by those extras
+#[derive(Clone, Deserialize, Debug)]
+struct Package {
+    name: String,
+    #[serde(default)]
+    extras: Vec<String>,
+    #[serde(default)]
+    dependencies: Vec<String>,
+}
+
+/// Reads the pyproject.toml file and returns the dependencies keyed by group. The `[project]`
+/// dependencies are in the main group, and the selected `[dependency-groups]` and
+/// `[tool.pdm.dev-dependencies]` groups are dev dependency groups
+pub fn get_dependencies_from_pyproject(
+    toml_file_path: &Path,
+    selection: &GroupSelection,
+) -> Result<HashMap<String, HashSet<String>>, Box<dyn Error>> {
+    let pyproject: PyProjectToml<Tool> = PyProjectToml::read(toml_file_path)?;
+    let mut groups = pyproject.get_dependencies(selection)?;
+
+    let dev_groups = pyproject.tool.and_then(|tool| tool.pdm?.dev_dependencies);
+    for (group, specs) in dev_groups.unwrap_or_default() {
+        if !selection.includes(&group, false) {
+            continue;
+        }
+        let dependencies = groups.entry(group).or_default();
+        for spec in specs.iter() {
+            // editable installs of local or VCS packages, e.g. `-e file:///${PROJECT_ROOT}/pkg`
+            if spec.starts_with("-e") {
+                continue;
+            }
+            dependencies.insert(parse_requirement(spec)?.name);
+        }
+    }
+
+    Ok(groups)
+}
+
+/// Checks the lock file for any package extras and returns a HashMap
+/// with the package name as the key and a Vec of the extras as the value
+pub fn check_lock_file_for_package_extras(
+    project_path: &Path,
+    manifest_packages: &HashSet<String>,
+    verbose: bool,
+) -> Result<HashMap<String, Vec<String>>, Box<dyn Error>> {
+    let lock_file_path = project_path.join("pdm.lock");
+    if !lock_file_path.exists() {
+        eprintln!("WARNING: Project has no lock file.");
+        return Ok(HashMap::new());
+    }
+
+    let mut lock_file_content: String = String::new();
+    fs::File::open(lock_file_path)?.read_to_string(&mut lock_file_content)?;
+    let lock_file: PdmLock = toml::from_str(&lock_file_content)?;
+
+    let manifest_packages: HashSet<String> = manifest_packages
+        .iter()
+        .map(|pkg| normalize_package_name(pkg))
+        .collect();
+
+    let mut extras: HashMap<String, Vec<String>> = HashMap::new();
+    for package in lock_file.package.iter().filter(|p| !p.extras.is_empty()) {
+        for spec in package.dependencies.iter() {
+            let pkg = normalize_package_name(&parse_requirement(spec)?.name);
+            // the extras entry depends on the package itself
+            if pkg == package.name || !manifest_packages.contains(&pkg) {
+                continue;
+            }
+            if verbose {
+                eprintln!(
+                    "Found {} - it is an extra dependency of {}",
+                    pkg, package.name
+                );
+            }
+            let entry = extras.entry(package.name.clone()).or_default();
+            if !entry.contains(&pkg) {
+                entry.push(pkg);
+            }
+        }
+    }
+
+    Ok(extras)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::MAIN_GROUP;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_get_dependencies_from_pyproject() {
+        let toml_file_path: PathBuf =
+            PathBuf::from("tests/fixtures/example_pdm_project/pyproject.toml");
+        let groups = get_dependencies_from_pyproject(&toml_file_path, &GroupSelection::default())
+            .expect("expected to get deps from manifest");
+        assert_eq!(groups.len(), 1);
+        let dependencies = &groups[MAIN_GROUP];
+        assert_eq!(dependencies.len(), 5);
+        assert_eq!(dependencies.get("rich"), Some(&"rich".to_string()));
+        assert_eq!(
+            dependencies.get("python-dotenv"),
+            Some(&"python-dotenv".to_string())
+        );
+    }
+
+    #[test]
+    fn test_get_dependencies_from_pyproject_with_dev() {
+        let toml_file_path: PathBuf =
+            PathBuf::from("tests/fixtures/example_pdm_project/pyproject.toml");
+        let selection = GroupSelection {
+            dev: true,
+            ..Default::default()
+        };
+        let groups = get_dependencies_from_pyproject(&toml_file_path, &selection)
+            .expect("expected to get deps from manifest");
+        assert_eq!(groups.len(), 3);
+        assert_eq!(
+            groups.get("test"),
+            Some(&["pytest".to_string(), "pytest-mock".to_string()].into())
+        );
+        assert_eq!(groups.get("lint"), Some(&["ruff".to_string()].into()));
+    }
+
+    #[test]
+    fn test_check_lock_file_for_package_extras() {
+        let project_path: PathBuf = PathBuf::from("tests/fixtures/example_pdm_project");
+        let manifest_packages: HashSet<String> = [
+            "rich".to_string(),
+            "pydantic".to_string(),
+            "email-validator".to_string(),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+
+        let extras = check_lock_file_for_package_extras(&project_path, &manifest_packages, false)
+            .expect("failed to parse lock file");
+
+        assert_eq!(extras.len(), 1);
+        assert_eq!(
+            extras.get("pydantic"),
+            Some(&vec!["email-validator".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_check_lock_file_for_package_extras_malformed_lockfile() {
+        let project_path = PathBuf::from("tests/fixtures/input/malformed_pdm_lockfile");
+        let manifest_packages: HashSet<String> = ["pydantic".to_string()].iter().cloned().collect();
+        let extras = check_lock_file_for_package_extras(&project_path, &manifest_packages, false);
+        assert!(extras.is_err());
+    }
+}
@@ -0,0 +1,129 @@
+//! PEP 508 dependency specifiers, e.g. `sentry-sdk[fastapi]>=1.34; python_version >= "3.11"`.
+//! This module parses requirement strings into the parts needed to match them with imports.
+use std::{error, fmt};
+
+/// A parsed PEP 508 requirement string
+#[derive(Clone, Debug, PartialEq)]
+pub struct Requirement {
+    /// The distribution name, as written in the requirement
+    pub name: String,
+    /// The extras requested for the distribution, e.g. `["fastapi"]` for `sentry-sdk[fastapi]`
+    pub extras: Vec<String>,
+    /// The environment marker, e.g. `python_version >= "3.11"`
+    pub marker: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RequirementError(String);
+
+impl fmt::Display for RequirementError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid requirement: {}", self.0)
+    }
+}
+
+impl error::Error for RequirementError {}
+
+/// Parses a PEP 508 requirement string, for example:
+/// ```ignore
+/// let requirement = parse_requirement("pydantic[email]>=1.10; python_version >= '3.8'")?;
+/// assert_eq!(requirement.name, "pydantic");
+/// assert_eq!(requirement.extras, vec!["email".to_string()]);
+/// ```
+pub fn parse_requirement(spec: &str) -> Result<Requirement, RequirementError> {
+    let invalid = || RequirementError(spec.to_string());
+    let (requirement, marker) = split_marker(spec);
+
+    let requirement = requirement.trim_start();
+    let name_len = requirement
+        .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+        .unwrap_or(requirement.len());
+    let name = &requirement[..name_len];
+    if name.is_empty() || !name.starts_with(|c: char| c.is_ascii_alphanumeric()) {
+        return Err(invalid());
+    }
+
+    let rest = requirement[name_len..].trim_start();
+    let extras = match rest.strip_prefix('[') {
+        Some(rest) => {
+            let end = rest.find(']').ok_or_else(invalid)?;
+            rest[..end]
+                .split(',')
+                .map(str::trim)
+                .filter(|extra| !extra.is_empty())
+                .map(str::to_string)
+                .collect()
+        }
+        None => Vec::new(),
+    };
+
+    Ok(Requirement {
+        name: name.to_string(),
+        extras,
+        marker,
+    })
+}
+
+/// Splits the environment marker from the rest of the requirement. For URL requirements
+/// the `;` must be preceded by whitespace, as a URL may itself contain a `;`
+fn split_marker(spec: &str) -> (&str, Option<String>) {
+    let is_url = spec.contains('@');
+    let position = spec
+        .char_indices()
+        .find(|&(i, c)| c == ';' && (!is_url || spec[..i].ends_with(char::is_whitespace)))
+        .map(|(i, _)| i);
+
+    match position {
+        Some(i) => {
+            let marker = spec[i + 1..].trim();
+            let marker = (!marker.is_empty()).then(|| marker.to_string());
+            (&spec[..i], marker)
+        }
+        None => (spec, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_requirement_name_only() {
+        let requirement = parse_requirement("requests").expect("expected a valid requirement");
+        assert_eq!(requirement.name, "requests");
+        assert!(requirement.extras.is_empty());
+        assert_eq!(requirement.marker, None);
+    }
+
+    #[test]
+    fn test_parse_requirement_with_version_extras_and_marker() {
+        let requirement =
+            parse_requirement("sentry-sdk[fastapi, celery] >=1.34,<2; python_version >= \"3.11\"")
+                .expect("expected a valid requirement");
+        assert_eq!(requirement.name, "sentry-sdk");
+        assert_eq!(
+            requirement.extras,
+            vec!["fastapi".to_string(), "celery".to_string()]
+        );
+        assert_eq!(
+            requirement.marker,
+            Some("python_version >= \"3.11\"".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_requirement_with_url() {
+        let requirement = parse_requirement(
+            "pip @ https://github.com/pypa/pip/archive/1.3.1.zip;v=1 ; os_name == 'posix'",
+        )
+        .expect("expected a valid requirement");
+        assert_eq!(requirement.name, "pip");
+        assert_eq!(requirement.marker, Some("os_name == 'posix'".to_string()));
+    }
+
+    #[test]
+    fn test_parse_requirement_invalid() {
+        assert!(parse_requirement(">=1.0").is_err());
+        assert!(parse_requirement("pydantic[email").is_err());
+    }
+}
@@ -0,0 +1,65 @@
+//! PEP 621 is the standard `[project]` table in pyproject.toml, where dependencies are
+//! declared as PEP 508 requirement strings rather than in a tool specific table.
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+};
+
+use serde::Deserialize;
+
+use crate::{formatters::normalize_package_name, pep508::parse_requirement};
+
+#[derive(Deserialize, Debug, Default)]
+pub struct Project {
+    name: Option<String>,
+    #[serde(default)]
+    dependencies: Vec<String>,
+    #[serde(rename = "optional-dependencies", default)]
+    optional_dependencies: HashMap<String, Vec<String>>,
+}
+
+impl Project {
+    /// Returns the package names declared in `[project].dependencies` and
+    /// `[project].optional-dependencies`. Optional dependencies that refer back to the project
+    /// itself, e.g. `all = ["example[redis,sentry]"]`, are skipped, comparing the PEP 503
+    /// normalized names
+    pub fn get_dependencies(&self) -> Result<HashSet<String>, Box<dyn Error>> {
+        let project_name = self.name.as_deref().map(normalize_package_name);
+        let mut dependencies = HashSet::new();
+        for spec in self
+            .dependencies
+            .iter()
+            .chain(self.optional_dependencies.values().flatten())
+        {
+            let requirement = parse_requirement(spec)?;
+            if project_name != Some(normalize_package_name(&requirement.name)) {
+                dependencies.insert(requirement.name);
+            }
+        }
+
+        Ok(dependencies)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_dependencies_skips_the_project_itself() {
+        let project = Project {
+            name: Some("my-project".to_string()),
+            dependencies: vec!["requests>=2.31".to_string()],
+            optional_dependencies: HashMap::from([
+                ("redis".to_string(), vec!["redis>=5.0".to_string()]),
+                ("all".to_string(), vec!["My_Project[redis]".to_string()]),
+                ("dev".to_string(), vec!["my.project[redis]".to_string()]),
+            ]),
+        };
+        let dependencies = project.get_dependencies().expect("valid requirements");
+        assert_eq!(
+            dependencies,
+            HashSet::from(["requests".to_string(), "redis".to_string()])
+        );
+    }
+}
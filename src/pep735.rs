@@ -0,0 +1,183 @@
+//! PEP 735 is the standard `[dependency-groups]` table in pyproject.toml, where each group is a
+//! list of PEP 508 requirement strings or `{include-group = "..."}` references to other groups.
+use std::{
+    collections::{HashMap, HashSet},
+    error::{self, Error},
+    fmt,
+};
+
+use serde::Deserialize;
+
+use crate::{formatters::normalize_package_name, pep508::parse_requirement};
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(transparent)]
+pub struct DependencyGroups(HashMap<String, Vec<GroupEntry>>);
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum GroupEntry {
+    Requirement(String),
+    Include {
+        #[serde(rename = "include-group")]
+        include_group: String,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+pub enum DependencyGroupError {
+    /// A group includes a group that is not declared
+    Missing(String),
+    /// A group includes itself, directly or through other groups
+    Cycle(Vec<String>),
+}
+
+impl fmt::Display for DependencyGroupError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DependencyGroupError::Missing(group) => {
+                write!(
+                    f,
+                    "Dependency group `{}` is included but not declared",
+                    group
+                )
+            }
+            DependencyGroupError::Cycle(groups) => {
+                write!(
+                    f,
+                    "Dependency groups include a cycle: {}",
+                    groups.join(" -> ")
+                )
+            }
+        }
+    }
+}
+
+impl error::Error for DependencyGroupError {}
+
+impl DependencyGroups {
+    /// Returns the package names of the selected groups keyed by the declared group name, with
+    /// the packages of included groups added to the including group
+    pub fn get_dependencies(
+        &self,
+        selected: impl Fn(&str) -> bool,
+    ) -> Result<HashMap<String, HashSet<String>>, Box<dyn Error>> {
+        let mut groups = HashMap::new();
+        for name in self.0.keys().filter(|name| selected(name)) {
+            let mut stack = Vec::new();
+            groups.insert(name.clone(), self.resolve(name, &mut stack)?);
+        }
+
+        Ok(groups)
+    }
+
+    /// Resolves the packages of a group, where the stack holds the groups being resolved so
+    /// an include of one of them is a cycle. Group names are compared once normalized
+    fn resolve(
+        &self,
+        name: &str,
+        stack: &mut Vec<String>,
+    ) -> Result<HashSet<String>, Box<dyn Error>> {
+        let normalized = normalize_package_name(name);
+        let Some((declared, entries)) = self
+            .0
+            .iter()
+            .find(|(group, _)| normalize_package_name(group) == normalized)
+        else {
+            return Err(Box::new(DependencyGroupError::Missing(name.to_string())));
+        };
+        if let Some(start) = stack
+            .iter()
+            .position(|group| normalize_package_name(group) == normalized)
+        {
+            let mut cycle = stack[start..].to_vec();
+            cycle.push(declared.clone());
+            return Err(Box::new(DependencyGroupError::Cycle(cycle)));
+        }
+
+        stack.push(declared.clone());
+        let mut dependencies = HashSet::new();
+        for entry in entries.iter() {
+            match entry {
+                GroupEntry::Requirement(spec) => {
+                    dependencies.insert(parse_requirement(spec)?.name);
+                }
+                GroupEntry::Include { include_group } => {
+                    dependencies.extend(self.resolve(include_group, stack)?);
+                }
+            }
+        }
+        stack.pop();
+
+        Ok(dependencies)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dependency_groups(toml: &str) -> DependencyGroups {
+        toml::from_str(toml).expect("expected valid dependency groups")
+    }
+
+    #[test]
+    fn test_get_dependencies_with_include_group() {
+        let groups = dependency_groups(
+            r#"
+            test = ["pytest>=7", "coverage[toml]"]
+            typing = ["mypy"]
+            dev = [{include-group = "Test"}, {include-group = "typing"}, "ruff"]
+            "#,
+        );
+        let dependencies = groups
+            .get_dependencies(|_| true)
+            .expect("expected to resolve groups");
+        assert_eq!(dependencies.len(), 3);
+        assert_eq!(
+            dependencies.get("typing"),
+            Some(&["mypy".to_string()].into())
+        );
+        assert_eq!(
+            dependencies.get("dev"),
+            Some(
+                &[
+                    "pytest".to_string(),
+                    "coverage".to_string(),
+                    "mypy".to_string(),
+                    "ruff".to_string()
+                ]
+                .into()
+            )
+        );
+    }
+
+    #[test]
+    fn test_get_dependencies_with_cycle() {
+        let groups = dependency_groups(
+            r#"
+            a = ["requests", {include-group = "b"}]
+            b = [{include-group = "a"}]
+            "#,
+        );
+        let error = groups
+            .get_dependencies(|group| group == "a")
+            .expect_err("expected a cycle");
+        assert_eq!(
+            error.to_string(),
+            "Dependency groups include a cycle: a -> b -> a"
+        );
+    }
+
+    #[test]
+    fn test_get_dependencies_with_missing_group() {
+        let groups = dependency_groups(r#"dev = [{include-group = "test"}]"#);
+        let error = groups
+            .get_dependencies(|_| true)
+            .expect_err("expected a missing group");
+        assert_eq!(
+            error.to_string(),
+            "Dependency group `test` is included but not declared"
+        );
+    }
+}
@@ -0,0 +1,161 @@
+//! Pipenv manages a project's dependencies with a Pipfile manifest and a Pipfile.lock lock file.
+//! This module parses the Pipfile and Pipfile.lock files to get package dependencies.
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+    string::String,
+};
+
+extern crate toml;
+use serde::Deserialize;
+
+use crate::formatters::normalize_package_name;
+
+#[derive(Deserialize, Debug)]
+struct Pipfile {
+    #[serde(default)]
+    packages: HashMap<String, serde_json::Value>,
+    #[serde(rename = "dev-packages", default)]
+    dev_packages: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+struct PipfileLock {
+    #[serde(default)]
+    default: HashMap<String, LockedPackage>,
+    #[serde(default)]
+    develop: HashMap<String, LockedPackage>,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+struct LockedPackage {
+    #[serde(default)]
+    extras: Vec<String>,
+}
+
+/// Reads the Pipfile and returns the dependencies. `[packages]` are the main dependencies and
+/// `[dev-packages]` the dev dependencies
+pub fn get_dependencies_from_pipfile(
+    pipfile_path: &PathBuf,
+    with_dev_deps: bool,
+) -> Result<HashSet<String>, Box<dyn Error>> {
+    let mut pipfile_content: String = String::new();
+    fs::File::open(pipfile_path)?.read_to_string(&mut pipfile_content)?;
+    let pipfile: Pipfile = toml::from_str(&pipfile_content)?;
+
+    let mut pipfile_dependencies: HashSet<String> = pipfile.packages.keys().cloned().collect();
+
+    if with_dev_deps {
+        pipfile_dependencies.extend(pipfile.dev_packages.keys().cloned());
+    }
+
+    Ok(pipfile_dependencies)
+}
+
+/// Checks the lock file for any package extras and returns a HashMap
+/// with the package name as the key and a Vec of the extras as the value.
+/// Pipfile.lock records the extras requested for a package but not the packages they install,
+/// so a manifest package is only matched to an extra of the same normalized name, e.g. `celery`
+/// is an extra of `sentry-sdk[celery]`, but `email-validator` is not an extra of `pydantic[email]`
+pub fn check_lock_file_for_package_extras(
+    project_path: &Path,
+    manifest_packages: &HashSet<String>,
+    verbose: bool,
+) -> Result<HashMap<String, Vec<String>>, Box<dyn Error>> {
+    let lock_file_path = project_path.join("Pipfile.lock");
+    if !lock_file_path.exists() {
+        eprintln!("WARNING: Project has no lock file.");
+        return Ok(HashMap::new());
+    }
+
+    let mut lock_file_content: String = String::new();
+    fs::File::open(lock_file_path)?.read_to_string(&mut lock_file_content)?;
+    let lock_file: PipfileLock = serde_json::from_str(&lock_file_content)?;
+
+    let manifest_names: HashMap<String, &String> = manifest_packages
+        .iter()
+        .map(|pkg| (normalize_package_name(pkg), pkg))
+        .collect();
+    let mut extras: HashMap<String, Vec<String>> = HashMap::new();
+    for (name, package) in lock_file.default.iter().chain(lock_file.develop.iter()) {
+        for extra in package.extras.iter() {
+            let Some(pkg) = manifest_names.get(&normalize_package_name(extra)) else {
+                continue;
+            };
+            if verbose {
+                eprintln!("Found {} - it is an extra dependency of {}", pkg, name);
+            }
+            extras
+                .entry(name.clone())
+                .or_default()
+                .push(pkg.to_string());
+        }
+    }
+
+    Ok(extras)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_dependencies_from_pipfile() {
+        let pipfile_path: PathBuf = PathBuf::from("tests/fixtures/example_pipenv_project/Pipfile");
+        let dependencies = get_dependencies_from_pipfile(&pipfile_path, false)
+            .expect("expected to get deps from Pipfile");
+        let expected: HashSet<String> = [
+            "celery".to_string(),
+            "redis".to_string(),
+            "pydantic".to_string(),
+            "email-validator".to_string(),
+            "sentry-sdk".to_string(),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+        assert_eq!(dependencies, expected);
+    }
+
+    #[test]
+    fn test_get_dependencies_from_pipfile_with_dev() {
+        let pipfile_path: PathBuf = PathBuf::from("tests/fixtures/example_pipenv_project/Pipfile");
+        let dependencies = get_dependencies_from_pipfile(&pipfile_path, true)
+            .expect("expected to get deps from Pipfile");
+        assert_eq!(dependencies.len(), 7);
+        assert_eq!(dependencies.get("pytest"), Some(&"pytest".to_string()));
+        assert_eq!(dependencies.get("mypy"), Some(&"mypy".to_string()));
+    }
+
+    #[test]
+    fn test_check_lock_file_for_package_extras() {
+        let project_path: PathBuf = PathBuf::from("tests/fixtures/example_pipenv_project");
+        let manifest_packages: HashSet<String> = [
+            "celery".to_string(),
+            "redis".to_string(),
+            "pydantic".to_string(),
+            "email-validator".to_string(),
+            "sentry-sdk".to_string(),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+
+        let extras = check_lock_file_for_package_extras(&project_path, &manifest_packages, false)
+            .expect("failed to parse lock file");
+
+        assert_eq!(extras.len(), 1);
+        assert_eq!(extras.get("sentry-sdk"), Some(&vec!["celery".to_string()]));
+    }
+
+    #[test]
+    fn test_check_lock_file_for_package_extras_malformed_lockfile() {
+        let project_path = PathBuf::from("tests/fixtures/input/malformed_pipfile_lock");
+        let manifest_packages: HashSet<String> = ["pydantic".to_string()].iter().cloned().collect();
+        let extras = check_lock_file_for_package_extras(&project_path, &manifest_packages, false);
+        assert!(extras.is_err());
+    }
+}
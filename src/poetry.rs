@@ -1,43 +1,63 @@
 //! Poetry is a tool for dependency management and packaging in Python.
 //! This module parses Poetry pyproject.toml and lock files to get package dependencies.
+//! Poetry 2.x projects may declare dependencies in the standard `[project]` table, these are
+//! read alongside the legacy `[tool.poetry]` tables. Dependency groups are read from
+//! `[tool.poetry.group.<name>.dependencies]`, and the legacy `[tool.poetry.dev-dependencies]`
+//! table is read as the `dev` group. The pyproject.toml file is parsed preserving its comments,
+//! so a dependency can be ignored with a trailing `# deptr: ignore` comment.
 use std::{
     collections::{HashMap, HashSet},
     error::Error,
     fs,
     io::Read,
-    path::{Path, PathBuf},
+    path::Path,
     string::String,
 };
 
 extern crate toml;
 use serde::Deserialize;
+use toml_edit::{Array, DocumentMut, Item, Table};
 
-#[derive(Deserialize, Debug)]
-struct PyProjectToml {
-    tool: Tool,
-}
+use crate::{
+    config::IgnoredPackage,
+    domain::{GroupSelection, DEV_GROUP, MAIN_GROUP},
+    formatters::normalize_package_name,
+    pep508::parse_requirement,
+    pyproject::PyProjectToml,
+};
+
+/// The comment that ignores the dependency declared on the same line, optionally followed by
+/// a reason, e.g. `uvicorn = "^0.27.0"  # deptr: ignore - started by the Procfile`
+const IGNORE_COMMENT: &str = "deptr: ignore";
 
 #[derive(Deserialize, Debug)]
 struct Tool {
-    poetry: Poetry,
+    poetry: Option<Poetry>,
 }
 
 #[derive(Deserialize, Debug)]
 struct Poetry {
-    dependencies: HashMap<String, serde_json::Value>,
+    dependencies: Option<HashMap<String, serde_json::Value>>,
     #[serde(rename = "dev-dependencies")]
     dev_dependencies: Option<HashMap<String, serde_json::Value>>,
-    group: Option<Group>,
+    #[serde(default)]
+    group: HashMap<String, Group>,
+    /// The packages and modules of the project itself
+    #[serde(default)]
+    packages: Vec<PoetryPackage>,
 }
 
 #[derive(Deserialize, Debug)]
-struct Group {
-    dev: Dev,
+struct PoetryPackage {
+    include: String,
 }
 
 #[derive(Deserialize, Debug)]
-struct Dev {
+struct Group {
     dependencies: Option<HashMap<String, serde_json::Value>>,
+    /// Optional groups are not installed by default
+    #[serde(default)]
+    optional: bool,
 }
 
 #[derive(Clone, Deserialize, Debug)]
@@ -49,43 +69,239 @@ struct PoetryLock {
 struct Package {
     name: String,
     extras: Option<HashMap<String, serde_json::Value>>,
+    #[serde(default)]
+    dependencies: HashMap<String, serde_json::Value>,
 }
 
-/// Reads the pyproject.toml file and returns the dependencies
+/// Reads the pyproject.toml file and returns the dependencies keyed by group. The `[project]`
+/// and `[tool.poetry.dependencies]` dependencies are in the main group, followed by the
+/// selected Poetry and `[dependency-groups]` dependency groups
 pub fn get_dependencies_from_pyproject(
-    toml_file_path: &PathBuf,
-    with_dev_deps: bool,
-) -> Result<HashSet<String>, Box<dyn Error>> {
+    toml_file_path: &Path,
+    selection: &GroupSelection,
+) -> Result<HashMap<String, HashSet<String>>, Box<dyn Error>> {
+    let pyproject: PyProjectToml<Tool> = PyProjectToml::read(toml_file_path)?;
+    let mut groups = pyproject.get_dependencies(selection)?;
+
+    let main_dependencies = groups.entry(MAIN_GROUP.to_string()).or_default();
+    let poetry = pyproject.tool.and_then(|tool| tool.poetry);
+    if let Some(dependencies) = poetry.as_ref().and_then(|p| p.dependencies.as_ref()) {
+        main_dependencies.extend(dependencies.keys().cloned());
+    }
+
+    // python is included as a dependency in poetry but we don't want to include it
+    main_dependencies.remove("python");
+
+    if let Some(poetry) = poetry {
+        for (group, dependencies) in get_group_dependencies(poetry) {
+            if selection.includes(&group, dependencies.optional) {
+                groups
+                    .entry(group)
+                    .or_default()
+                    .extend(dependencies.packages);
+            }
+        }
+    }
+
+    Ok(groups)
+}
+
+/// Reads the pyproject.toml file preserving its formatting and comments
+fn read_pyproject(toml_file_path: &Path) -> Result<DocumentMut, Box<dyn Error>> {
     let mut toml_content: String = String::new();
     fs::File::open(toml_file_path)?.read_to_string(&mut toml_content)?;
-    let pyproject: PyProjectToml = toml::from_str(&toml_content)?;
+    Ok(toml_content.parse::<DocumentMut>()?)
+}
 
-    let mut pyproject_dependencies: HashSet<_> =
-        pyproject.tool.poetry.dependencies.keys().cloned().collect();
+/// Reads the dependencies of the pyproject.toml file with a trailing `# deptr: ignore` comment,
+/// from the `[project]` dependencies, the `[dependency-groups]`, the PDM dev dependencies, the
+/// Hatch environments and the Poetry dependency tables
+pub fn get_inline_ignores_from_pyproject(
+    toml_file_path: &Path,
+) -> Result<Vec<IgnoredPackage>, Box<dyn Error>> {
+    let document = read_pyproject(toml_file_path)?;
+    let mut ignores = Vec::new();
 
-    // python is included as a dependency in poetry but we don't want to include it
-    pyproject_dependencies.remove("python");
+    let project = document.get("project");
+    let tool = document.get("tool");
+    let hatch_environments = table_values(
+        tool.and_then(|tool| tool.get("hatch"))
+            .and_then(|hatch| hatch.get("envs")),
+    )
+    .flat_map(|environment| {
+        ["dependencies", "extra-dependencies"]
+            .into_iter()
+            .filter_map(|key| environment.get(key))
+    });
+    let arrays = project
+        .and_then(|project| project.get("dependencies"))
+        .into_iter()
+        .chain(table_values(
+            project.and_then(|project| project.get("optional-dependencies")),
+        ))
+        .chain(table_values(document.get("dependency-groups")))
+        .chain(table_values(
+            tool.and_then(|tool| tool.get("pdm"))
+                .and_then(|pdm| pdm.get("dev-dependencies")),
+        ))
+        .chain(hatch_environments)
+        .filter_map(Item::as_array);
+    for array in arrays {
+        ignores.extend(array_ignores(array)?);
+    }
 
-    if with_dev_deps {
-        let dev_dependencies = get_dev_dependencies(pyproject);
-        pyproject_dependencies.extend(dev_dependencies);
+    let poetry = document.get("tool").and_then(|tool| tool.get("poetry"));
+    let groups = poetry
+        .and_then(|poetry| poetry.get("group"))
+        .and_then(Item::as_table_like);
+    let tables = poetry
+        .and_then(|poetry| poetry.get("dependencies"))
+        .into_iter()
+        .chain(poetry.and_then(|poetry| poetry.get("dev-dependencies")))
+        .chain(groups.into_iter().flat_map(|groups| {
+            groups
+                .iter()
+                .filter_map(|(_, group)| group.get("dependencies"))
+        }))
+        .filter_map(Item::as_table);
+    for table in tables {
+        ignores.extend(table_ignores(table));
     }
 
-    Ok(pyproject_dependencies)
+    Ok(ignores)
+}
+
+/// Returns the values of a table, or nothing when the item is missing or not a table
+fn table_values(item: Option<&Item>) -> impl Iterator<Item = &Item> {
+    item.and_then(Item::as_table_like)
+        .into_iter()
+        .flat_map(|table| table.iter().map(|(_, value)| value))
 }
 
-fn get_dev_dependencies(pyproject: PyProjectToml) -> HashSet<String> {
-    let mut all_dev_deps = HashSet::new();
-    if let Some(dev_dependencies) = pyproject.tool.poetry.dev_dependencies {
-        all_dev_deps.extend(dev_dependencies.keys().cloned());
+/// Returns the ignored requirements of an array, where the comment after an item is in the
+/// prefix of the next item, or in the trailing whitespace of the array for the last item. The
+/// comment after an array written on one line ignores all of its requirements
+fn array_ignores(array: &Array) -> Result<Vec<IgnoredPackage>, Box<dyn Error>> {
+    let line_ignore = if array.to_string().trim().contains('\n') {
+        None
+    } else {
+        array
+            .decor()
+            .suffix()
+            .and_then(|suffix| suffix.as_str())
+            .and_then(ignore_comment)
+    };
+    let values: Vec<_> = array.iter().collect();
+    let mut ignores = Vec::new();
+    for (i, value) in values.iter().enumerate() {
+        let Some(spec) = value.as_str() else {
+            continue;
+        };
+        let comment = match values.get(i + 1) {
+            Some(next) => next.decor().prefix().and_then(|prefix| prefix.as_str()),
+            None => array.trailing().as_str(),
+        };
+        let suffix = value.decor().suffix().and_then(|suffix| suffix.as_str());
+        if let Some(reason) = suffix
+            .and_then(ignore_comment)
+            .or_else(|| comment.and_then(ignore_comment))
+            .or_else(|| line_ignore.clone())
+        {
+            ignores.push(IgnoredPackage {
+                package: parse_requirement(spec)?.name,
+                reason,
+            });
+        }
     }
-    if let Some(group) = pyproject.tool.poetry.group {
-        if let Some(dev_dependencies) = group.dev.dependencies {
-            all_dev_deps.extend(dev_dependencies.keys().cloned());
+
+    Ok(ignores)
+}
+
+/// Returns the ignored dependencies of a Poetry table, where the comment is in the suffix of
+/// the value
+fn table_ignores(table: &Table) -> Vec<IgnoredPackage> {
+    table
+        .iter()
+        .filter_map(|(package, item)| {
+            let suffix = item.as_value()?.decor().suffix()?.as_str()?;
+            let reason = ignore_comment(suffix)?;
+            Some(IgnoredPackage {
+                package: package.to_string(),
+                reason,
+            })
+        })
+        .collect()
+}
+
+/// Finds the `# deptr: ignore` comment on the first line of the raw text after a value and
+/// returns its reason, if any
+fn ignore_comment(raw: &str) -> Option<Option<String>> {
+    let line = raw.lines().next()?;
+    let (_, comment) = line.split_once('#')?;
+    let reason = comment.trim().strip_prefix(IGNORE_COMMENT)?;
+    // the reason is separated by whitespace or a dash, e.g. `# deptr: ignored` is not an ignore
+    if !(reason.is_empty() || reason.starts_with(|c: char| c.is_whitespace() || c == '-')) {
+        return None;
+    }
+    let reason = reason.trim_start_matches(|c: char| c.is_whitespace() || c == '-');
+    let reason = reason.trim();
+    Some((!reason.is_empty()).then(|| reason.to_string()))
+}
+
+/// The packages of a dependency group and whether the group is optional
+struct GroupDependencies {
+    packages: HashSet<String>,
+    optional: bool,
+}
+
+fn get_group_dependencies(poetry: Poetry) -> HashMap<String, GroupDependencies> {
+    let mut groups: HashMap<String, GroupDependencies> = HashMap::new();
+    if let Some(dev_dependencies) = poetry.dev_dependencies {
+        groups.insert(
+            DEV_GROUP.to_string(),
+            GroupDependencies {
+                packages: dev_dependencies.keys().cloned().collect(),
+                optional: false,
+            },
+        );
+    }
+    for (name, group) in poetry.group {
+        let entry = groups.entry(name).or_insert_with(|| GroupDependencies {
+            packages: HashSet::new(),
+            optional: group.optional,
+        });
+        if let Some(dependencies) = group.dependencies {
+            entry.packages.extend(dependencies.keys().cloned());
         }
     }
 
-    all_dev_deps
+    groups
+}
+
+/// Returns the top level modules of the `packages` of `[tool.poetry]`, e.g. `acme` for
+/// `packages = [{ include = "acme", from = "src" }]`. Glob patterns are skipped
+pub fn get_packages_from_pyproject(project_path: &Path) -> Result<Vec<String>, Box<dyn Error>> {
+    let toml_file_path = project_path.join("pyproject.toml");
+    if !toml_file_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let pyproject: PyProjectToml<Tool> = PyProjectToml::read(&toml_file_path)?;
+    let packages = pyproject
+        .tool
+        .and_then(|tool| tool.poetry)
+        .map(|poetry| poetry.packages)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|package| !package.include.contains(['*', '?', '[']))
+        .filter_map(|package| {
+            let top_level = package.include.split('/').next()?;
+            let module = top_level.strip_suffix(".py").unwrap_or(top_level);
+            Some(module.to_string())
+        })
+        .collect();
+
+    Ok(packages)
 }
 
 /// Checks the lock file for any package extras and returns a HashMap
@@ -106,7 +322,7 @@ pub fn check_lock_file_for_package_extras(
 ) -> Result<HashMap<String, Vec<String>>, Box<dyn Error>> {
     let lock_file_path = project_path.join("poetry.lock");
     if !lock_file_path.exists() {
-        println!("WARNING: Project has no lock file.");
+        eprintln!("WARNING: Project has no lock file.");
         return Ok(HashMap::new());
     }
 
@@ -130,7 +346,7 @@ pub fn check_lock_file_for_package_extras(
                             {
                                 let fmt_pkg = pkg.split('(').next().unwrap_or("").trim();
                                 if verbose {
-                                    println!(
+                                    eprintln!(
                                         "Found {} - it is an extra dependency of {}",
                                         fmt_pkg, package.name
                                     );
@@ -147,16 +363,64 @@ pub fn check_lock_file_for_package_extras(
     Ok(extras)
 }
 
+/// Reads the `[package.dependencies]` of each locked package and returns the dependency
+/// graph, keyed by the normalized package name, for example:
+/// ```ignore
+///  {
+///     "pydantic": ["typing-extensions"],
+///     "typing-extensions": [],
+/// }
+/// ```
+pub fn get_dependency_graph_from_lock_file(
+    project_path: &Path,
+) -> Result<HashMap<String, Vec<String>>, Box<dyn Error>> {
+    let lock_file_path = project_path.join("poetry.lock");
+    if !lock_file_path.exists() {
+        return Ok(HashMap::new());
+    }
+
+    let lock_file: PoetryLock = toml::from_str(&fs::read_to_string(lock_file_path)?)?;
+    let graph = lock_file
+        .package
+        .iter()
+        .map(|package| {
+            let mut dependencies: Vec<String> = package
+                .dependencies
+                .keys()
+                .map(|dependency| normalize_package_name(dependency))
+                .collect();
+            dependencies.sort();
+            (normalize_package_name(&package.name), dependencies)
+        })
+        .collect();
+
+    Ok(graph)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::path::PathBuf;
+
+    fn dev_selection() -> GroupSelection {
+        GroupSelection {
+            dev: true,
+            ..Default::default()
+        }
+    }
+
+    fn all_dependencies(groups: &HashMap<String, HashSet<String>>) -> HashSet<String> {
+        groups.values().flatten().cloned().collect()
+    }
 
     #[test]
     fn test_get_dependencies_from_pyproject() {
         let toml_file_path: PathBuf =
             PathBuf::from("tests/fixtures/example_project/pyproject.toml");
-        let dependencies = get_dependencies_from_pyproject(&toml_file_path, false)
+        let groups = get_dependencies_from_pyproject(&toml_file_path, &GroupSelection::default())
             .expect("expected to get deps from manifest");
+        assert_eq!(groups.len(), 1);
+        let dependencies = &groups[MAIN_GROUP];
         assert_eq!(dependencies.len(), 12);
         assert_eq!(dependencies.get("fastapi"), Some(&"fastapi".to_string()));
         assert_eq!(dependencies.get("redis"), Some(&"redis".to_string()));
@@ -183,8 +447,9 @@ mod tests {
     fn test_get_dependencies_from_pyproject_with_dev() {
         let toml_file_path: PathBuf =
             PathBuf::from("tests/fixtures/example_project/pyproject.toml");
-        let dependencies = get_dependencies_from_pyproject(&toml_file_path, true)
+        let groups = get_dependencies_from_pyproject(&toml_file_path, &dev_selection())
             .expect("expected to get deps from manifest");
+        let dependencies = all_dependencies(&groups);
         assert_eq!(dependencies.len(), 15);
         assert_eq!(dependencies.get("fastapi"), Some(&"fastapi".to_string()));
         assert_eq!(dependencies.get("pytest"), Some(&"pytest".to_string()));
@@ -193,8 +458,9 @@ mod tests {
     #[test]
     fn test_get_dependencies_from_pyproject_with_dev_from_old_poetry() {
         let toml_file_path: PathBuf = PathBuf::from("tests/fixtures/input/old/pyproject.toml");
-        let dependencies = get_dependencies_from_pyproject(&toml_file_path, true)
+        let groups = get_dependencies_from_pyproject(&toml_file_path, &dev_selection())
             .expect("expected to get deps from manifest");
+        let dependencies = all_dependencies(&groups);
         assert_eq!(dependencies.len(), 14);
         assert_eq!(dependencies.get("fastapi"), Some(&"fastapi".to_string()));
         assert_eq!(dependencies.get("pytest"), Some(&"pytest".to_string()));
@@ -203,17 +469,91 @@ mod tests {
     #[test]
     fn test_get_dependencies_from_pyproject_with_dev_no_dev_in_poetry() {
         let toml_file_path: PathBuf = PathBuf::from("tests/fixtures/input/no_dev/pyproject.toml");
-        let dependencies = get_dependencies_from_pyproject(&toml_file_path, true)
+        let groups = get_dependencies_from_pyproject(&toml_file_path, &dev_selection())
             .expect("expected to get deps from manifest");
+        let dependencies = all_dependencies(&groups);
         assert_eq!(dependencies.len(), 11);
         assert_eq!(dependencies.get("fastapi"), Some(&"fastapi".to_string()));
     }
 
+    #[test]
+    fn test_get_dependencies_from_pyproject_groups() {
+        let toml_file_path: PathBuf =
+            PathBuf::from("tests/fixtures/input/poetry_groups/pyproject.toml");
+        let groups = get_dependencies_from_pyproject(&toml_file_path, &dev_selection())
+            .expect("expected to get deps from manifest");
+        assert_eq!(groups.len(), 4);
+        assert_eq!(
+            groups.get("dev"),
+            Some(&["pre-commit".to_string(), "ipython".to_string()].into())
+        );
+        assert_eq!(
+            groups.get("test"),
+            Some(&["pytest".to_string(), "pytest-cov".to_string()].into())
+        );
+        assert_eq!(groups.get("lint"), Some(&["ruff".to_string()].into()));
+        assert_eq!(groups.get("docs"), None);
+    }
+
+    #[test]
+    fn test_get_dependencies_from_pyproject_named_groups() {
+        let toml_file_path: PathBuf =
+            PathBuf::from("tests/fixtures/input/poetry_groups/pyproject.toml");
+        let selection = GroupSelection {
+            names: vec!["docs".to_string()],
+            ..Default::default()
+        };
+        let groups = get_dependencies_from_pyproject(&toml_file_path, &selection)
+            .expect("expected to get deps from manifest");
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups.get("docs"), Some(&["mkdocs".to_string()].into()));
+
+        let selection = GroupSelection {
+            all: true,
+            ..Default::default()
+        };
+        let groups = get_dependencies_from_pyproject(&toml_file_path, &selection)
+            .expect("expected to get deps from manifest");
+        assert_eq!(groups.len(), 5);
+    }
+
+    #[test]
+    fn test_get_dependencies_from_pyproject_pep621_project() {
+        let toml_file_path: PathBuf = PathBuf::from("tests/fixtures/input/pep621/pyproject.toml");
+        let groups = get_dependencies_from_pyproject(&toml_file_path, &GroupSelection::default())
+            .expect("expected to get deps from manifest");
+        assert_eq!(groups.len(), 1);
+        let dependencies = &groups[MAIN_GROUP];
+        let expected: HashSet<String> = [
+            "fastapi".to_string(),
+            "pydantic".to_string(),
+            "sentry-sdk".to_string(),
+            "python-dotenv".to_string(),
+            "redis".to_string(),
+            "psycopg2-binary".to_string(),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+        assert_eq!(dependencies, &expected);
+    }
+
+    #[test]
+    fn test_get_dependencies_from_pyproject_pep621_project_with_dev() {
+        let toml_file_path: PathBuf = PathBuf::from("tests/fixtures/input/pep621/pyproject.toml");
+        let groups = get_dependencies_from_pyproject(&toml_file_path, &dev_selection())
+            .expect("expected to get deps from manifest");
+        let dependencies = all_dependencies(&groups);
+        assert_eq!(dependencies.len(), 8);
+        assert_eq!(dependencies.get("pytest"), Some(&"pytest".to_string()));
+        assert_eq!(dependencies.get("ruff"), Some(&"ruff".to_string()));
+    }
+
     #[test]
     fn test_get_dependencies_from_pyproject_malformed_pyproject_toml() {
         let toml_file_path: PathBuf =
             PathBuf::from("tests/fixtures/input/malformed_pyproject/pyproject.toml");
-        let dependencies = get_dependencies_from_pyproject(&toml_file_path, true);
+        let dependencies = get_dependencies_from_pyproject(&toml_file_path, &dev_selection());
         assert!(dependencies.is_err());
     }
 
@@ -255,6 +595,101 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_get_dependency_graph_from_lock_file() {
+        let project_path: PathBuf = PathBuf::from("tests/fixtures/example_project");
+        let graph =
+            get_dependency_graph_from_lock_file(&project_path).expect("failed to parse lock file");
+        assert_eq!(
+            graph.get("pydantic"),
+            Some(&vec!["typing-extensions".to_string()])
+        );
+        assert_eq!(graph.get("pylint").map(Vec::len), Some(8));
+        assert_eq!(graph.get("email-validator").map(Vec::len), Some(2));
+    }
+
+    #[test]
+    fn test_get_packages_from_pyproject() {
+        let project_path = PathBuf::from("tests/fixtures/input/first_party");
+        let packages =
+            get_packages_from_pyproject(&project_path).expect("failed to parse pyproject.toml");
+        assert_eq!(packages, vec!["acme", "acme_plugins", "tasks"]);
+
+        let project_path = PathBuf::from("tests/fixtures/input/module_map");
+        let packages =
+            get_packages_from_pyproject(&project_path).expect("failed to parse pyproject.toml");
+        assert!(packages.is_empty());
+    }
+
+    #[test]
+    fn test_get_inline_ignores_from_pyproject() {
+        let toml_file_path = PathBuf::from("tests/fixtures/input/inline_ignores/pyproject.toml");
+        let ignores = get_inline_ignores_from_pyproject(&toml_file_path)
+            .expect("failed to parse pyproject.toml");
+        let ignore = |package: &str, reason: Option<&str>| IgnoredPackage {
+            package: package.to_string(),
+            reason: reason.map(str::to_string),
+        };
+        assert_eq!(
+            ignores,
+            vec![
+                ignore("uvicorn", Some("started by the Procfile")),
+                ignore("psycopg2-binary", None),
+                ignore("python-dotenv", Some("loaded by the settings module")),
+                ignore("redis", Some("loaded by the cache backend")),
+                ignore("pytest-cov", Some("used by the CI coverage step")),
+                ignore("gunicorn", None),
+                ignore("sentry-sdk", Some("initialised from the environment")),
+                ignore("mkdocs", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_inline_ignores_from_pyproject_pdm_and_hatch_tables() {
+        let toml_file_path =
+            PathBuf::from("tests/fixtures/input/inline_ignores_tools/pyproject.toml");
+        let ignores = get_inline_ignores_from_pyproject(&toml_file_path)
+            .expect("failed to parse pyproject.toml");
+        let mut packages: Vec<(String, Option<String>)> = ignores
+            .into_iter()
+            .map(|ignore| (ignore.package, ignore.reason))
+            .collect();
+        packages.sort();
+        assert_eq!(
+            packages,
+            vec![
+                ("coverage".to_string(), None),
+                (
+                    "mkdocs-material".to_string(),
+                    Some("themes the docs".to_string())
+                ),
+                (
+                    "pytest-xdist".to_string(),
+                    Some("run by the CI workflow".to_string())
+                ),
+                ("ruff".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ignore_comment() {
+        assert_eq!(ignore_comment("  # deptr: ignore\n"), Some(None));
+        assert_eq!(
+            ignore_comment(" # deptr: ignore - a reason"),
+            Some(Some("a reason".to_string()))
+        );
+        assert_eq!(ignore_comment(" # noqa"), None);
+        assert_eq!(ignore_comment(" # deptr: ignored-for-now"), None);
+        assert_eq!(ignore_comment(" # deptr: ignore: a reason"), None);
+        assert_eq!(
+            ignore_comment(" # deptr: ignore-a reason"),
+            Some(Some("a reason".to_string()))
+        );
+        assert_eq!(ignore_comment("\n    # deptr: ignore"), None);
+    }
+
     #[test]
     fn test_check_lock_file_for_package_extras_malformed_lockfile() {
         let project_path = PathBuf::from("tests/fixtures/input/malformed_lockfile");
@@ -0,0 +1,52 @@
+//! pyproject.toml is the standard manifest of a Python project, where the `[project]` and
+//! `[dependency-groups]` tables are shared by every package manager and the `[tool]` table holds
+//! the configuration of each tool. This module reads the standard tables, along with the
+//! `[tool]` table of a package manager.
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fs,
+    path::Path,
+};
+
+use serde::{de::DeserializeOwned, Deserialize};
+
+use crate::{
+    domain::{GroupSelection, MAIN_GROUP},
+    pep621::Project,
+    pep735::DependencyGroups,
+};
+
+/// The pyproject.toml file, where `T` is the `[tool]` table of a package manager
+#[derive(Deserialize, Debug)]
+pub struct PyProjectToml<T> {
+    pub project: Option<Project>,
+    #[serde(rename = "dependency-groups", default)]
+    pub dependency_groups: DependencyGroups,
+    pub tool: Option<T>,
+}
+
+impl<T: DeserializeOwned> PyProjectToml<T> {
+    /// Reads the pyproject.toml file
+    pub fn read(toml_file_path: &Path) -> Result<Self, Box<dyn Error>> {
+        Ok(toml::from_str(&fs::read_to_string(toml_file_path)?)?)
+    }
+
+    /// Returns the dependencies of the standard tables keyed by group. The `[project]`
+    /// dependencies are in the main group, followed by the selected `[dependency-groups]`
+    pub fn get_dependencies(
+        &self,
+        selection: &GroupSelection,
+    ) -> Result<HashMap<String, HashSet<String>>, Box<dyn Error>> {
+        let mut groups: HashMap<String, HashSet<String>> = HashMap::new();
+        if let Some(project) = &self.project {
+            groups.insert(MAIN_GROUP.to_string(), project.get_dependencies()?);
+        }
+        let dependency_groups = self
+            .dependency_groups
+            .get_dependencies(|group| selection.includes(group, false))?;
+        groups.extend(dependency_groups);
+
+        Ok(groups)
+    }
+}
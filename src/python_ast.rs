@@ -1,85 +1,298 @@
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     error::Error,
-    fs,
+    fmt, fs,
     path::{Path, PathBuf},
     string::String,
 };
 
-use rustpython_parser::{ast, Parse};
+use rustpython_parser::{ast, source_code::RandomLocator, text_size::TextSize, Parse};
+use serde::Serialize;
 
 use super::python_std_lib::is_std_lib_module;
 
 const EXCLUDED_DIRS: [&str; 4] = ["venv", ".pytest_cache", ".ruff_cache", ".venv"];
 
-/// Recursively walks the path provided, parses all .py files and
-/// returns a HashSet of all Python non-standard library imports
-pub fn get_imports_from_src(directory_path: &Path) -> Result<HashSet<String>, Box<dyn Error>> {
+/// The functions that import a module named by a string
+const IMPORT_FUNCTIONS: [&str; 2] = ["importlib.import_module", "__import__"];
+/// The functions that check whether a module can be imported, without importing it
+const FIND_FUNCTIONS: [&str; 4] = [
+    "importlib.util.find_spec",
+    "importlib.find_loader",
+    "pkgutil.find_loader",
+    "pkgutil.get_loader",
+];
+
+/// Where an import statement is, which tells whether the module is needed at runtime. The
+/// variants are ordered from the least to the most restrictive context
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ImportContext {
+    /// Imported whenever the code runs
+    Runtime,
+    /// Under a condition on `sys.version_info` or `sys.platform`
+    Conditional,
+    /// In a `try` block catching ImportError, or its handler
+    Optional,
+    /// Under `if TYPE_CHECKING:`, only imported by type checkers
+    TypeChecking,
+}
+
+/// How a module is imported
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImportKind {
+    /// `import a.b`
+    Import,
+    /// `from a.b import c`
+    ImportFrom,
+    /// `importlib.import_module("a.b")` and the like, with a string literal
+    Dynamic,
+}
+
+/// An import of a non-standard library module in the project source code
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportRecord {
+    /// The full dotted path of the module, e.g. `google.cloud.storage`
+    pub module: String,
+    /// The top level package of the module, e.g. `google`, which packages are matched with
+    pub top_level: String,
+    /// The names imported from the module by `from a.b import c`
+    pub names: Vec<String>,
+    /// The file of the import, relative to the project path
+    pub file: PathBuf,
+    pub line: usize,
+    pub column: usize,
+    pub kind: ImportKind,
+    pub context: ImportContext,
+}
+
+impl ImportRecord {
+    /// Whether the import is of the module, or of one of its submodules, e.g. `google.protobuf`
+    /// for `from google.protobuf import descriptor` or `from google import protobuf`. A top
+    /// level module is matched with the top level package. The module path of a namespace
+    /// package distribution is derived from its name, e.g. `google.api.core` for
+    /// `google-api-core` whose module is `google.api_core`, so `.` and `_` are not told apart
+    pub fn imports_module(&self, module: &str) -> bool {
+        if !module.contains('.') {
+            return self.top_level == module;
+        }
+        let module = squash_module_path(module);
+        std::iter::once(self.module.clone())
+            .chain(
+                self.names
+                    .iter()
+                    .map(|name| format!("{}.{}", self.module, name)),
+            )
+            .any(|path| {
+                path.match_indices('.')
+                    .map(|(i, _)| &path[..i])
+                    .chain(std::iter::once(path.as_str()))
+                    .any(|prefix| squash_module_path(prefix) == module)
+            })
+    }
+
+    pub fn location(&self) -> ImportLocation {
+        ImportLocation {
+            file: self.file.clone(),
+            line: self.line,
+            column: self.column,
+        }
+    }
+}
+
+/// Where an import is in the project source code, displayed as `file:line`
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct ImportLocation {
+    /// The file of the import, relative to the project path
+    pub file: PathBuf,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for ImportLocation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.file.display(), self.line)
+    }
+}
+
+/// The imports found in the project source code
+#[derive(Debug, Default)]
+pub struct SourceImports {
+    /// The non-standard library imports, keyed by their top level package
+    pub imports: HashMap<String, Vec<ImportRecord>>,
+    /// The top level modules and packages of the project itself, which are never matched with a
+    /// dependency of the same name
+    pub first_party_modules: HashSet<String>,
+}
+
+impl SourceImports {
+    /// Adds top level modules of the project, e.g. the `packages` of `[tool.poetry]`, and drops
+    /// the imports of every first-party module
+    pub fn add_first_party_modules(&mut self, modules: impl IntoIterator<Item = String>) {
+        self.first_party_modules.extend(modules);
+        let first_party_modules = &self.first_party_modules;
+        self.imports
+            .retain(|module, _| !first_party_modules.contains(module));
+    }
+}
+
+/// The directories of the project that are scanned for imports
+#[derive(Clone, Debug, Default)]
+pub struct SourceSelection {
+    /// The source roots, relative to the project path. The whole project is scanned when empty
+    pub roots: Vec<PathBuf>,
+    /// The directories excluded from the scan, by name or by path relative to the project path,
+    /// in addition to the virtualenv and cache directories
+    pub exclude: Vec<String>,
+}
+
+impl SourceSelection {
+    /// The directories the modules of the project are imported from: the project directory,
+    /// the `src` directory of a src-layout and the source roots. A directory that is itself a
+    /// package is imported through its parent, so it is not an import root
+    fn import_roots(&self, project_path: &Path) -> Vec<PathBuf> {
+        let roots = std::iter::once(Path::new("src"))
+            .chain(self.roots.iter().map(PathBuf::as_path))
+            .map(|root| project_path.join(root))
+            .filter(|root| root.is_dir() && !is_package(root));
+        std::iter::once(project_path.to_path_buf())
+            .chain(roots)
+            .collect()
+    }
+
+    /// Whether the directory, relative to the project path, is excluded from the scan
+    fn is_excluded(&self, relative_path: &Path, dir_name: &str) -> bool {
+        EXCLUDED_DIRS.contains(&dir_name)
+            || self
+                .exclude
+                .iter()
+                .any(|exclude| exclude == dir_name || Path::new(exclude) == relative_path)
+    }
+}
+
+/// Recursively walks the source roots of the project path, parses all .py files and
+/// returns all Python non-standard library imports
+pub fn get_imports_from_src(
+    directory_path: &Path,
+    sources: &SourceSelection,
+) -> Result<SourceImports, Box<dyn Error>> {
     let ext = "py";
+    let mut source_imports = SourceImports::default();
+    let import_roots = sources.import_roots(directory_path);
 
-    match find_files_with_extension(directory_path, ext) {
-        Ok(result) => Ok(result),
-        Err(e) => Err(e),
+    if sources.roots.is_empty() {
+        find_files_with_extension(
+            directory_path,
+            directory_path,
+            ext,
+            sources,
+            &import_roots,
+            &mut source_imports,
+        )?;
+    }
+    for root in sources.roots.iter() {
+        let root_path = directory_path.join(root);
+        if !root_path.is_dir() {
+            eprintln!("WARNING: Source root `{}` not found.", root.display());
+        }
+        find_files_with_extension(
+            directory_path,
+            &root_path,
+            ext,
+            sources,
+            &import_roots,
+            &mut source_imports,
+        )?;
     }
+    source_imports.add_first_party_modules(Vec::new());
+    Ok(source_imports)
 }
 
 fn find_files_with_extension(
+    root: &Path,
     dir: &Path,
     extension: &str,
-) -> Result<HashSet<String>, Box<dyn Error>> {
-    let mut result: HashSet<String> = HashSet::new();
-
-    if dir.is_dir() {
-        for entry in std::fs::read_dir(dir)? {
-            let path = entry?.path();
-            // let file_name = path.file_name().unwrap().to_string_lossy().to_string();
-            let file_name = if let Some(file_name) = path.file_name() {
-                file_name.to_string_lossy().to_string()
-            } else {
-                continue;
-            };
-
-            // Check if the directory should be excluded
-            if path.is_dir() && EXCLUDED_DIRS.contains(&file_name.as_str()) {
-                continue;
-            }
-
-            if path.is_file() {
-                if let Some(file_extension) = path.extension() {
-                    if file_extension == extension {
-                        let new_imports = get_imports_from_python_module(&path);
-                        match new_imports {
-                            Ok(imports) => {
-                                result.extend(imports);
-                            }
-                            Err(e) => {
-                                return Err(e);
-                            }
-                        }
-                    }
+    sources: &SourceSelection,
+    import_roots: &[PathBuf],
+    source_imports: &mut SourceImports,
+) -> Result<(), Box<dyn Error>> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let file_name = if let Some(file_name) = path.file_name() {
+            file_name.to_string_lossy().to_string()
+        } else {
+            continue;
+        };
+        let relative_path = path.strip_prefix(root).unwrap_or(&path).to_path_buf();
+
+        // Check if the directory should be excluded
+        if path.is_dir() && sources.is_excluded(&relative_path, &file_name) {
+            continue;
+        }
+
+        if path.is_file() {
+            if path.extension().is_some_and(|ext| ext == extension) {
+                for record in get_imports_from_python_module(&path, &relative_path)? {
+                    source_imports
+                        .imports
+                        .entry(record.top_level.clone())
+                        .or_default()
+                        .push(record);
                 }
-            } else if path.is_dir() {
-                match find_files_with_extension(&path, extension) {
-                    Ok(new_imports) => {
-                        result.extend(new_imports);
-                    }
-                    Err(e) => {
-                        return Err(e);
-                    }
+                if let Some(module) = first_party_module(import_roots, &path) {
+                    source_imports.first_party_modules.insert(module);
                 }
             }
+        } else if path.is_dir() {
+            find_files_with_extension(
+                root,
+                &path,
+                extension,
+                sources,
+                import_roots,
+                source_imports,
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the top level module a Python file is imported as, when it can be imported from one
+/// of the import roots: a file directly in an import root is a top level module, while a file
+/// in a package belongs to the outermost package of the directories above it. The files of
+/// other directories without `__init__.py`, such as `scripts/` or `tests/`, are run as
+/// scripts rather than imported, so they are never first-party modules
+fn first_party_module(import_roots: &[PathBuf], path: &Path) -> Option<String> {
+    let mut module = path.file_stem()?.to_string_lossy().to_string();
+    let mut dir = path.parent()?;
+    loop {
+        if import_roots.iter().any(|root| root == dir) {
+            return (module != "__init__").then_some(module);
         }
+        if !is_package(dir) {
+            return None;
+        }
+        module = dir.file_name()?.to_string_lossy().to_string();
+        dir = dir.parent()?;
     }
+}
 
-    Ok(result)
+/// Whether the directory is a regular Python package
+fn is_package(dir: &Path) -> bool {
+    dir.join("__init__.py").is_file()
 }
 
 /// Reads the Python modules and the import statements, filters out Python standard
-/// library modules and returns the imports
+/// library modules and returns a record of each import, in the file relative to the
+/// project path
 fn get_imports_from_python_module(
-    module_path: &PathBuf,
-) -> Result<HashSet<String>, Box<dyn Error>> {
-    let mut imports: HashSet<String> = HashSet::new();
+    module_path: &Path,
+    file: &Path,
+) -> Result<Vec<ImportRecord>, Box<dyn Error>> {
     let module_str = module_path
         .to_str()
         .ok_or("Failed to convert path to string")?;
@@ -87,51 +300,499 @@ fn get_imports_from_python_module(
 
     let python_statements = ast::Suite::parse(&python_source, module_str)?;
 
-    for statement in python_statements.iter() {
+    let mut visitor = ImportVisitor::new(file, &python_source);
+    visitor.visit_body(&python_statements);
+    Ok(visitor.records)
+}
+
+/// Walks every statement of a module, including the bodies of functions, classes and control
+/// flow blocks, to find the import statements wherever they are, and every expression to find
+/// the dynamic imports
+struct ImportVisitor<'a> {
+    /// The file of the module, relative to the project path
+    file: &'a Path,
+    /// Converts the offsets of the statements to line and column numbers
+    locator: RandomLocator<'a>,
+    /// The non-standard library imports
+    records: Vec<ImportRecord>,
+    /// The context of the statements being visited
+    context: ImportContext,
+    /// The qualified names of the modules and functions bound by import statements, keyed by
+    /// their local name, e.g. `import_module` for `from importlib import import_module`
+    bindings: HashMap<String, String>,
+}
+
+impl<'a> ImportVisitor<'a> {
+    fn new(file: &'a Path, source: &'a str) -> Self {
+        Self {
+            file,
+            locator: RandomLocator::new(source),
+            records: Vec::new(),
+            context: ImportContext::Runtime,
+            bindings: HashMap::new(),
+        }
+    }
+
+    fn visit_body(&mut self, body: &[ast::Stmt]) {
+        for statement in body.iter() {
+            self.visit_stmt(statement);
+        }
+    }
+
+    /// Visits a body in a context, where a body nested in a more restrictive context keeps it,
+    /// e.g. an optional import under `if TYPE_CHECKING:` is only imported by type checkers
+    fn visit_body_in(&mut self, body: &[ast::Stmt], context: ImportContext) {
+        let outer = self.context;
+        self.context = outer.max(context);
+        self.visit_body(body);
+        self.context = outer;
+    }
+
+    fn visit_stmt(&mut self, statement: &ast::Stmt) {
         match statement {
             ast::Stmt::Import(import_stmt) => {
-                import_stmt.names.iter().for_each(|name| {
-                    if !is_std_lib_module(name.name.as_str()) {
-                        imports.insert(name.name.as_str().to_string());
-                    }
-                });
+                for name in import_stmt.names.iter() {
+                    // `import a.b` binds `a`, and `import a.b as c` binds `c` to `a.b`
+                    let (local, qualified) = match &name.asname {
+                        Some(asname) => (asname.to_string(), name.name.to_string()),
+                        None => {
+                            let top_level = top_level_module(name.name.as_str());
+                            (top_level.to_string(), top_level.to_string())
+                        }
+                    };
+                    self.bindings.insert(local, qualified);
+                    self.add_import(
+                        name.name.as_str(),
+                        Vec::new(),
+                        ImportKind::Import,
+                        import_stmt.range.start(),
+                    );
+                }
             }
             ast::Stmt::ImportFrom(import_from_stmt) => {
-                let results: Result<Vec<_>, _> = import_from_stmt
-                    .module
-                    .as_ref()
-                    .iter()
-                    .map(|module| {
-                        if !is_std_lib_module(module.as_str()) {
-                            let module_name: Result<&str, _> = module
-                                .as_str()
-                                .split('.')
-                                .next()
-                                .ok_or("Module name not found");
-                            imports.insert(module_name?.to_string());
-                        }
-                        Ok(()) as Result<(), Box<dyn Error>>
-                    })
-                    .collect();
-
-                results?;
+                // Relative imports, e.g. `from .adapters import repo`, are always first-party
+                let is_relative = import_from_stmt
+                    .level
+                    .is_some_and(|level| level.to_u32() > 0);
+                if let Some(module) = import_from_stmt.module.as_ref().filter(|_| !is_relative) {
+                    for alias in import_from_stmt.names.iter() {
+                        let local = alias.asname.as_ref().unwrap_or(&alias.name);
+                        self.bindings
+                            .insert(local.to_string(), format!("{}.{}", module, alias.name));
+                    }
+                    let names = import_from_stmt
+                        .names
+                        .iter()
+                        .map(|alias| alias.name.to_string())
+                        .collect();
+                    self.add_import(
+                        module.as_str(),
+                        names,
+                        ImportKind::ImportFrom,
+                        import_from_stmt.range.start(),
+                    );
+                }
+            }
+            ast::Stmt::FunctionDef(function) => {
+                self.visit_exprs(&function.decorator_list);
+                self.visit_arguments(&function.args);
+                self.visit_body(&function.body);
+            }
+            ast::Stmt::AsyncFunctionDef(function) => {
+                self.visit_exprs(&function.decorator_list);
+                self.visit_arguments(&function.args);
+                self.visit_body(&function.body);
             }
-            // no other use cases
+            ast::Stmt::ClassDef(class) => {
+                self.visit_exprs(&class.decorator_list);
+                self.visit_exprs(&class.bases);
+                for keyword in class.keywords.iter() {
+                    self.visit_expr(&keyword.value);
+                }
+                self.visit_body(&class.body);
+            }
+            ast::Stmt::Return(return_stmt) => self.visit_optional_expr(&return_stmt.value),
+            ast::Stmt::Delete(delete_stmt) => self.visit_exprs(&delete_stmt.targets),
+            ast::Stmt::Assign(assign) => {
+                self.visit_exprs(&assign.targets);
+                self.visit_expr(&assign.value);
+            }
+            ast::Stmt::TypeAlias(type_alias) => self.visit_expr(&type_alias.value),
+            ast::Stmt::AugAssign(assign) => {
+                self.visit_expr(&assign.target);
+                self.visit_expr(&assign.value);
+            }
+            ast::Stmt::AnnAssign(assign) => {
+                self.visit_expr(&assign.target);
+                self.visit_optional_expr(&assign.value);
+            }
+            ast::Stmt::For(for_stmt) => {
+                self.visit_expr(&for_stmt.iter);
+                self.visit_body(&for_stmt.body);
+                self.visit_body(&for_stmt.orelse);
+            }
+            ast::Stmt::AsyncFor(for_stmt) => {
+                self.visit_expr(&for_stmt.iter);
+                self.visit_body(&for_stmt.body);
+                self.visit_body(&for_stmt.orelse);
+            }
+            ast::Stmt::While(while_stmt) => {
+                self.visit_expr(&while_stmt.test);
+                self.visit_body(&while_stmt.body);
+                self.visit_body(&while_stmt.orelse);
+            }
+            ast::Stmt::If(if_stmt) => {
+                self.visit_expr(&if_stmt.test);
+                if is_type_checking(&if_stmt.test) {
+                    self.visit_body_in(&if_stmt.body, ImportContext::TypeChecking);
+                    self.visit_body(&if_stmt.orelse);
+                } else if is_version_or_platform_check(&if_stmt.test) {
+                    self.visit_body_in(&if_stmt.body, ImportContext::Conditional);
+                    self.visit_body_in(&if_stmt.orelse, ImportContext::Conditional);
+                } else {
+                    self.visit_body(&if_stmt.body);
+                    self.visit_body(&if_stmt.orelse);
+                }
+            }
+            ast::Stmt::With(with_stmt) => {
+                self.visit_with_items(&with_stmt.items);
+                self.visit_body(&with_stmt.body);
+            }
+            ast::Stmt::AsyncWith(with_stmt) => {
+                self.visit_with_items(&with_stmt.items);
+                self.visit_body(&with_stmt.body);
+            }
+            ast::Stmt::Match(match_stmt) => {
+                self.visit_expr(&match_stmt.subject);
+                for case in match_stmt.cases.iter() {
+                    if let Some(guard) = &case.guard {
+                        self.visit_expr(guard);
+                    }
+                    self.visit_body(&case.body);
+                }
+            }
+            ast::Stmt::Raise(raise) => {
+                self.visit_optional_expr(&raise.exc);
+                self.visit_optional_expr(&raise.cause);
+            }
+            ast::Stmt::Try(try_stmt) => {
+                self.visit_try(
+                    &try_stmt.body,
+                    &try_stmt.handlers,
+                    &try_stmt.orelse,
+                    &try_stmt.finalbody,
+                );
+            }
+            ast::Stmt::TryStar(try_stmt) => {
+                self.visit_try(
+                    &try_stmt.body,
+                    &try_stmt.handlers,
+                    &try_stmt.orelse,
+                    &try_stmt.finalbody,
+                );
+            }
+            ast::Stmt::Assert(assert) => {
+                self.visit_expr(&assert.test);
+                self.visit_optional_expr(&assert.msg);
+            }
+            ast::Stmt::Expr(expr) => self.visit_expr(&expr.value),
+            // no other statement has a body or an expression
             _ => {}
         }
     }
 
-    Ok(imports)
+    fn visit_arguments(&mut self, arguments: &ast::Arguments) {
+        let defaults = arguments
+            .posonlyargs
+            .iter()
+            .chain(arguments.args.iter())
+            .chain(arguments.kwonlyargs.iter())
+            .filter_map(|arg| arg.default.as_deref());
+        for default in defaults {
+            self.visit_expr(default);
+        }
+    }
+
+    fn visit_with_items(&mut self, items: &[ast::WithItem]) {
+        for item in items.iter() {
+            self.visit_expr(&item.context_expr);
+        }
+    }
+
+    fn visit_exprs(&mut self, exprs: &[ast::Expr]) {
+        for expr in exprs.iter() {
+            self.visit_expr(expr);
+        }
+    }
+
+    fn visit_optional_expr(&mut self, expr: &Option<Box<ast::Expr>>) {
+        if let Some(expr) = expr {
+            self.visit_expr(expr);
+        }
+    }
+
+    /// Visits an expression and the expressions nested in it, including lambdas and
+    /// comprehensions, to find the dynamic imports
+    fn visit_expr(&mut self, expr: &ast::Expr) {
+        match expr {
+            ast::Expr::BoolOp(bool_op) => self.visit_exprs(&bool_op.values),
+            ast::Expr::NamedExpr(named) => self.visit_expr(&named.value),
+            ast::Expr::BinOp(bin_op) => {
+                self.visit_expr(&bin_op.left);
+                self.visit_expr(&bin_op.right);
+            }
+            ast::Expr::UnaryOp(unary_op) => self.visit_expr(&unary_op.operand),
+            ast::Expr::Lambda(lambda) => {
+                self.visit_arguments(&lambda.args);
+                self.visit_expr(&lambda.body);
+            }
+            ast::Expr::IfExp(if_exp) => {
+                self.visit_expr(&if_exp.test);
+                self.visit_expr(&if_exp.body);
+                self.visit_expr(&if_exp.orelse);
+            }
+            ast::Expr::Dict(dict) => {
+                self.visit_exprs(&dict.values);
+                for key in dict.keys.iter().flatten() {
+                    self.visit_expr(key);
+                }
+            }
+            ast::Expr::Set(set) => self.visit_exprs(&set.elts),
+            ast::Expr::ListComp(comp) => {
+                self.visit_expr(&comp.elt);
+                self.visit_comprehensions(&comp.generators);
+            }
+            ast::Expr::SetComp(comp) => {
+                self.visit_expr(&comp.elt);
+                self.visit_comprehensions(&comp.generators);
+            }
+            ast::Expr::DictComp(comp) => {
+                self.visit_expr(&comp.key);
+                self.visit_expr(&comp.value);
+                self.visit_comprehensions(&comp.generators);
+            }
+            ast::Expr::GeneratorExp(comp) => {
+                self.visit_expr(&comp.elt);
+                self.visit_comprehensions(&comp.generators);
+            }
+            ast::Expr::Await(await_expr) => self.visit_expr(&await_expr.value),
+            ast::Expr::Yield(yield_expr) => self.visit_optional_expr(&yield_expr.value),
+            ast::Expr::YieldFrom(yield_from) => self.visit_expr(&yield_from.value),
+            ast::Expr::Compare(compare) => {
+                self.visit_expr(&compare.left);
+                self.visit_exprs(&compare.comparators);
+            }
+            ast::Expr::Call(call) => {
+                self.visit_call(call);
+                self.visit_expr(&call.func);
+                self.visit_exprs(&call.args);
+                for keyword in call.keywords.iter() {
+                    self.visit_expr(&keyword.value);
+                }
+            }
+            ast::Expr::FormattedValue(formatted) => self.visit_expr(&formatted.value),
+            ast::Expr::JoinedStr(joined) => self.visit_exprs(&joined.values),
+            ast::Expr::Attribute(attribute) => self.visit_expr(&attribute.value),
+            ast::Expr::Subscript(subscript) => {
+                self.visit_expr(&subscript.value);
+                self.visit_expr(&subscript.slice);
+            }
+            ast::Expr::Starred(starred) => self.visit_expr(&starred.value),
+            ast::Expr::List(list) => self.visit_exprs(&list.elts),
+            ast::Expr::Tuple(tuple) => self.visit_exprs(&tuple.elts),
+            ast::Expr::Slice(slice) => {
+                self.visit_optional_expr(&slice.lower);
+                self.visit_optional_expr(&slice.upper);
+                self.visit_optional_expr(&slice.step);
+            }
+            // names and constants have no nested expressions
+            ast::Expr::Constant(_) | ast::Expr::Name(_) => {}
+        }
+    }
+
+    fn visit_comprehensions(&mut self, generators: &[ast::Comprehension]) {
+        for generator in generators.iter() {
+            self.visit_expr(&generator.iter);
+            self.visit_exprs(&generator.ifs);
+        }
+    }
+
+    /// Adds the module of a dynamic import with a string literal, e.g.
+    /// `importlib.import_module("redis")` or `__import__("yaml")`. Checking whether a module
+    /// can be imported, e.g. `importlib.util.find_spec("ujson")`, is an optional import. Only
+    /// the functions of importlib and pkgutil are dynamic imports, so a method of the same name,
+    /// e.g. `registry.find_spec("name")`, is not
+    fn visit_call(&mut self, call: &ast::ExprCall) {
+        let Some(function) = self.qualified_name(&call.func) else {
+            return;
+        };
+        let context = if IMPORT_FUNCTIONS.contains(&function.as_str()) {
+            self.context
+        } else if FIND_FUNCTIONS.contains(&function.as_str()) {
+            self.context.max(ImportContext::Optional)
+        } else {
+            return;
+        };
+        let Some(ast::Expr::Constant(constant)) = call.args.first() else {
+            return;
+        };
+        let ast::Constant::Str(module) = &constant.value else {
+            return;
+        };
+        // relative imports are modules of the project
+        if module.starts_with('.') {
+            return;
+        }
+
+        let outer = self.context;
+        self.context = context;
+        self.add_import(module, Vec::new(), ImportKind::Dynamic, call.range.start());
+        self.context = outer;
+    }
+
+    /// Returns the qualified name of a name or an attribute, resolving the local name it starts
+    /// with, e.g. `importlib.util.find_spec` for `util.find_spec` after `from importlib import
+    /// util`. The builtin `__import__` is its own qualified name
+    fn qualified_name(&self, expr: &ast::Expr) -> Option<String> {
+        match expr {
+            ast::Expr::Name(name) => match self.bindings.get(name.id.as_str()) {
+                Some(qualified) => Some(qualified.clone()),
+                None => (name.id.as_str() == "__import__").then(|| name.id.to_string()),
+            },
+            ast::Expr::Attribute(attribute) => {
+                let value = self.qualified_name(&attribute.value)?;
+                Some(format!("{}.{}", value, attribute.attr))
+            }
+            _ => None,
+        }
+    }
+
+    fn visit_try(
+        &mut self,
+        body: &[ast::Stmt],
+        handlers: &[ast::ExceptHandler],
+        orelse: &[ast::Stmt],
+        finalbody: &[ast::Stmt],
+    ) {
+        let catches_import_error =
+            handlers
+                .iter()
+                .any(|ast::ExceptHandler::ExceptHandler(handler)| {
+                    handler.type_.as_deref().is_none_or(is_import_error)
+                });
+        if catches_import_error {
+            self.visit_body_in(body, ImportContext::Optional);
+        } else {
+            self.visit_body(body);
+        }
+        for ast::ExceptHandler::ExceptHandler(handler) in handlers.iter() {
+            if catches_import_error {
+                self.visit_body_in(&handler.body, ImportContext::Optional);
+            } else {
+                self.visit_body(&handler.body);
+            }
+        }
+        self.visit_body(orelse);
+        self.visit_body(finalbody);
+    }
+
+    fn add_import(&mut self, module: &str, names: Vec<String>, kind: ImportKind, offset: TextSize) {
+        let top_level = top_level_module(module);
+        if is_std_lib_module(top_level) {
+            return;
+        }
+
+        let location = self.locator.locate(offset);
+        self.records.push(ImportRecord {
+            module: module.to_string(),
+            top_level: top_level.to_string(),
+            names,
+            file: self.file.to_path_buf(),
+            line: location.row.get() as usize,
+            column: location.column.get() as usize,
+            kind,
+            context: self.context,
+        });
+    }
+}
+
+/// Whether the condition is `TYPE_CHECKING` or `typing.TYPE_CHECKING`
+fn is_type_checking(test: &ast::Expr) -> bool {
+    match test {
+        ast::Expr::Name(name) => name.id.as_str() == "TYPE_CHECKING",
+        ast::Expr::Attribute(attribute) => attribute.attr.as_str() == "TYPE_CHECKING",
+        _ => false,
+    }
+}
+
+/// Whether the condition refers to `sys.version_info` or `sys.platform`, e.g.
+/// `sys.version_info >= (3, 11)` or `sys.platform.startswith("win")`
+fn is_version_or_platform_check(test: &ast::Expr) -> bool {
+    match test {
+        ast::Expr::Attribute(attribute) => {
+            let is_sys =
+                matches!(&*attribute.value, ast::Expr::Name(name) if name.id.as_str() == "sys");
+            (is_sys && matches!(attribute.attr.as_str(), "version_info" | "platform"))
+                || is_version_or_platform_check(&attribute.value)
+        }
+        ast::Expr::Compare(compare) => {
+            is_version_or_platform_check(&compare.left)
+                || compare.comparators.iter().any(is_version_or_platform_check)
+        }
+        ast::Expr::BoolOp(bool_op) => bool_op.values.iter().any(is_version_or_platform_check),
+        ast::Expr::UnaryOp(unary_op) => is_version_or_platform_check(&unary_op.operand),
+        ast::Expr::Subscript(subscript) => is_version_or_platform_check(&subscript.value),
+        ast::Expr::Call(call) => is_version_or_platform_check(&call.func),
+        _ => false,
+    }
+}
+
+/// Whether the exception caught by an except clause is ImportError or ModuleNotFoundError
+fn is_import_error(exception: &ast::Expr) -> bool {
+    match exception {
+        ast::Expr::Name(name) => matches!(name.id.as_str(), "ImportError" | "ModuleNotFoundError"),
+        ast::Expr::Tuple(tuple) => tuple.elts.iter().any(is_import_error),
+        _ => false,
+    }
+}
+
+/// Removes the `.` and `_` separators of a module path and lowercases it
+fn squash_module_path(module: &str) -> String {
+    module
+        .chars()
+        .filter(|c| !matches!(c, '.' | '_'))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Returns the top level package of a module, e.g. `google` for `google.cloud.storage`
+pub fn top_level_module(module: &str) -> &str {
+    module.split('.').next().unwrap_or(module)
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeSet;
+
     use super::*;
 
+    /// The contexts each top level package is imported in by a module
+    fn contexts_by_module(records: &[ImportRecord]) -> HashMap<String, BTreeSet<ImportContext>> {
+        let mut contexts: HashMap<String, BTreeSet<ImportContext>> = HashMap::new();
+        for record in records {
+            contexts
+                .entry(record.top_level.clone())
+                .or_default()
+                .insert(record.context);
+        }
+        contexts
+    }
+
     #[test]
     fn test_get_imports_from_src() {
         let test_path = Path::new("tests/fixtures/example_project");
-        let result = get_imports_from_src(test_path).unwrap();
+        let result = get_imports_from_src(test_path, &SourceSelection::default()).unwrap();
+        let imports: HashSet<String> = result.imports.keys().cloned().collect();
         let expected: HashSet<String> = [
             "requests".to_string(),
             "alembic".to_string(),
@@ -144,6 +805,228 @@ mod tests {
         .cloned()
         .collect();
 
-        assert_eq!(result, expected);
+        assert_eq!(imports, expected);
+        let locations: Vec<ImportLocation> = result.imports["requests"]
+            .iter()
+            .map(ImportRecord::location)
+            .collect();
+        assert_eq!(
+            locations,
+            vec![ImportLocation {
+                file: PathBuf::from("main.py"),
+                line: 8,
+                column: 1,
+            }]
+        );
+        assert!(result.first_party_modules.contains("main"));
+        assert!(result.first_party_modules.contains("src"));
+        assert!(!result.first_party_modules.contains("adapters"));
+    }
+
+    #[test]
+    fn test_get_imports_from_src_with_first_party_modules() {
+        let test_path = Path::new("tests/fixtures/input/first_party");
+        let mut result = get_imports_from_src(test_path, &SourceSelection::default()).unwrap();
+        let imports: HashSet<String> = result.imports.keys().cloned().collect();
+        let expected = HashSet::from([
+            "requests".to_string(),
+            "pytest".to_string(),
+            "acme_plugins".to_string(),
+        ]);
+        assert_eq!(imports, expected);
+        // `scripts/requests.py` is not importable, so the `requests` imports are kept
+        assert_eq!(
+            result.first_party_modules,
+            HashSet::from(["acme".to_string()])
+        );
+
+        result.add_first_party_modules(vec!["acme_plugins".to_string()]);
+        assert!(!result.imports.contains_key("acme_plugins"));
+    }
+
+    #[test]
+    fn test_get_imports_from_src_with_source_selection() {
+        let test_path = Path::new("tests/fixtures/example_project");
+        let sources = SourceSelection {
+            roots: vec![PathBuf::from("src")],
+            exclude: vec!["adapters".to_string()],
+        };
+        let result = get_imports_from_src(test_path, &sources).unwrap();
+        let imports: HashSet<String> = result.imports.keys().cloned().collect();
+        let expected = HashSet::from(["fastapi".to_string(), "pydantic".to_string()]);
+        assert_eq!(imports, expected);
+        let files: Vec<&PathBuf> = result.imports["fastapi"]
+            .iter()
+            .map(|record| &record.file)
+            .collect();
+        assert_eq!(files, vec![&PathBuf::from("src/server.py")]);
+
+        let sources = SourceSelection {
+            roots: Vec::new(),
+            exclude: vec!["src/adapters".to_string()],
+        };
+        let result = get_imports_from_src(test_path, &sources).unwrap();
+        assert!(result.imports.contains_key("requests"));
+        assert!(!result.imports.contains_key("alembic"));
+    }
+
+    #[test]
+    fn test_get_imports_from_python_module_records() {
+        let module_path = PathBuf::from("tests/fixtures/input/import_names/app/__init__.py");
+        let file = Path::new("app/__init__.py");
+        let records = get_imports_from_python_module(&module_path, file).unwrap();
+        let google = records
+            .iter()
+            .find(|record| record.top_level == "google")
+            .expect("expected the google import");
+        assert_eq!(
+            google,
+            &ImportRecord {
+                module: "google.cloud".to_string(),
+                top_level: "google".to_string(),
+                names: vec!["storage".to_string()],
+                file: file.to_path_buf(),
+                line: 5,
+                column: 1,
+                kind: ImportKind::ImportFrom,
+                context: ImportContext::Runtime,
+            }
+        );
+        let cv2 = records
+            .iter()
+            .find(|record| record.top_level == "cv2")
+            .expect("expected the cv2 import");
+        assert_eq!(cv2.kind, ImportKind::Import);
+        assert!(cv2.names.is_empty());
+        assert_eq!(cv2.location().to_string(), "app/__init__.py:1");
+    }
+
+    #[test]
+    fn test_get_imports_from_python_module_with_nested_imports() {
+        let module_path = PathBuf::from("tests/fixtures/input/nested_imports/app/service.py");
+        let records =
+            get_imports_from_python_module(&module_path, Path::new("app/service.py")).unwrap();
+        let imports: HashSet<String> = contexts_by_module(&records).into_keys().collect();
+        let expected: HashSet<String> = [
+            "redis", "orjson", "ujson", "celery", "boto3", "httpx", "numpy", "rich", "lxml",
+            "click", "anyio",
+        ]
+        .iter()
+        .map(|import| import.to_string())
+        .collect();
+        assert_eq!(imports, expected);
+        let celery = records
+            .iter()
+            .find(|record| record.module == "celery")
+            .expect("expected the celery import");
+        assert_eq!((celery.line, celery.column), (19, 9));
+    }
+
+    #[test]
+    fn test_get_imports_from_python_module_with_contexts() {
+        let module_path = PathBuf::from("tests/fixtures/input/import_contexts/app/__init__.py");
+        let records =
+            get_imports_from_python_module(&module_path, Path::new("app/__init__.py")).unwrap();
+        let imports = contexts_by_module(&records);
+        let contexts = |import: &str| imports.get(import).cloned().unwrap_or_default();
+        assert_eq!(
+            contexts("requests"),
+            BTreeSet::from([ImportContext::Runtime])
+        );
+        assert_eq!(
+            contexts("mypy_boto3_s3"),
+            BTreeSet::from([ImportContext::TypeChecking])
+        );
+        assert_eq!(
+            contexts("orjson"),
+            BTreeSet::from([ImportContext::Optional])
+        );
+        assert_eq!(contexts("ujson"), BTreeSet::from([ImportContext::Optional]));
+        assert_eq!(
+            contexts("tomli"),
+            BTreeSet::from([ImportContext::Conditional])
+        );
+        assert_eq!(
+            contexts("colorama"),
+            BTreeSet::from([ImportContext::Conditional])
+        );
+        assert_eq!(
+            contexts("pandas"),
+            BTreeSet::from([ImportContext::Runtime, ImportContext::TypeChecking])
+        );
+        assert_eq!(
+            contexts("numpy"),
+            BTreeSet::from([ImportContext::TypeChecking])
+        );
+        assert_eq!(contexts("yaml"), BTreeSet::from([ImportContext::Runtime]));
+    }
+
+    #[test]
+    fn test_get_imports_from_python_module_with_dynamic_imports() {
+        let module_path = PathBuf::from("tests/fixtures/input/dynamic_imports/app/plugins.py");
+        let records =
+            get_imports_from_python_module(&module_path, Path::new("app/plugins.py")).unwrap();
+        assert!(records
+            .iter()
+            .filter(|record| record.top_level == "redis")
+            .all(|record| record.kind == ImportKind::Dynamic));
+        let imports = contexts_by_module(&records);
+        let contexts = |import: &str| imports.get(import).cloned().unwrap_or_default();
+        let mut modules: Vec<&String> = imports.keys().collect();
+        modules.sort();
+        assert_eq!(
+            modules,
+            vec![
+                "celery",
+                "lxml",
+                "msgpack",
+                "orjson",
+                "redis",
+                "rich",
+                "sentry_sdk",
+                "ujson",
+                "yaml",
+                "zstandard"
+            ]
+        );
+        assert_eq!(contexts("redis"), BTreeSet::from([ImportContext::Runtime]));
+        assert_eq!(contexts("ujson"), BTreeSet::from([ImportContext::Optional]));
+        assert_eq!(
+            contexts("orjson"),
+            BTreeSet::from([ImportContext::Optional])
+        );
+        assert_eq!(contexts("rich"), BTreeSet::from([ImportContext::Runtime]));
+        assert_eq!(
+            contexts("lxml"),
+            BTreeSet::from([ImportContext::TypeChecking])
+        );
+    }
+
+    #[test]
+    fn test_import_record_imports_module() {
+        let record = |module: &str, names: &[&str]| ImportRecord {
+            module: module.to_string(),
+            top_level: top_level_module(module).to_string(),
+            names: names.iter().map(|name| name.to_string()).collect(),
+            file: PathBuf::from("app.py"),
+            line: 1,
+            column: 1,
+            kind: ImportKind::ImportFrom,
+            context: ImportContext::Runtime,
+        };
+        let descriptor = record("google.protobuf", &["descriptor"]);
+        assert!(descriptor.imports_module("google"));
+        assert!(descriptor.imports_module("google.protobuf"));
+        assert!(!descriptor.imports_module("google.cloud.storage"));
+        assert!(record("google", &["protobuf"]).imports_module("google.protobuf"));
+        assert!(!record("google.cloud.storage", &[]).imports_module("google.protobuf"));
+        assert!(record("google.api_core", &["retry"]).imports_module("google.api.core"));
+        assert!(!record("google.protobuf_extra", &[]).imports_module("google.protobuf"));
+    }
+
+    #[test]
+    fn test_top_level_module() {
+        assert_eq!(top_level_module("google.cloud.storage"), "google");
+        assert_eq!(top_level_module("requests"), "requests");
     }
 }
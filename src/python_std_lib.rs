@@ -189,6 +189,7 @@ fn python_std_lib() -> HashMap<&'static str, bool> {
     std_lib_modules.insert("tkinter", true);
     std_lib_modules.insert("token", true);
     std_lib_modules.insert("tokenize", true);
+    std_lib_modules.insert("tomllib", true);
     std_lib_modules.insert("trace", true);
     std_lib_modules.insert("traceback", true);
     std_lib_modules.insert("tracemalloc", true);
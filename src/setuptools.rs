@@ -0,0 +1,328 @@
+//! setuptools is the original Python build tool, where dependencies are declared in setup.cfg
+//! or passed to the `setup()` call in setup.py.
+//! This module reads setup.cfg and statically extracts literals from setup.py, without
+//! executing any Python, to get package dependencies.
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+    string::String,
+};
+
+use rustpython_parser::{ast, Parse};
+use serde::de::IgnoredAny;
+
+use crate::{pep508::parse_requirement, pyproject::PyProjectToml};
+
+/// Extras that are conventionally used for development tooling rather than optional features,
+/// these are only tracked as dev dependencies
+const DEV_EXTRAS: [&str; 7] = ["dev", "develop", "docs", "lint", "test", "testing", "tests"];
+
+/// The requirement strings declared by a setuptools project
+#[derive(Debug, Default)]
+struct SetupRequirements {
+    install_requires: Vec<String>,
+    extras_require: HashMap<String, Vec<String>>,
+}
+
+impl SetupRequirements {
+    fn get_dependencies(&self, with_dev_deps: bool) -> Result<HashSet<String>, Box<dyn Error>> {
+        let extras = self
+            .extras_require
+            .iter()
+            .filter(|(extra, _)| with_dev_deps || !DEV_EXTRAS.contains(&extra.as_str()))
+            .flat_map(|(_, specs)| specs);
+
+        let mut dependencies = HashSet::new();
+        for spec in self.install_requires.iter().chain(extras) {
+            dependencies.insert(parse_requirement(spec)?.name);
+        }
+
+        Ok(dependencies)
+    }
+}
+
+/// Reads the setup.cfg, setup.py and pyproject.toml files that exist in the project
+/// and returns the dependencies. The pyproject.toml `[dependency-groups]` are dev dependencies
+pub fn get_dependencies_from_setuptools(
+    project_path: &Path,
+    with_dev_deps: bool,
+) -> Result<HashSet<String>, Box<dyn Error>> {
+    let mut dependencies: HashSet<String> = HashSet::new();
+
+    let cfg_path = project_path.join("setup.cfg");
+    if cfg_path.is_file() {
+        dependencies.extend(get_dependencies_from_setup_cfg(&cfg_path, with_dev_deps)?);
+    }
+
+    let setup_py_path = project_path.join("setup.py");
+    if setup_py_path.is_file() {
+        dependencies.extend(get_dependencies_from_setup_py(
+            &setup_py_path,
+            with_dev_deps,
+        )?);
+    }
+
+    let toml_path = project_path.join("pyproject.toml");
+    if toml_path.is_file() {
+        let pyproject: PyProjectToml<IgnoredAny> = PyProjectToml::read(&toml_path)?;
+        if let Some(project) = pyproject.project {
+            dependencies.extend(project.get_dependencies()?);
+        }
+        if with_dev_deps {
+            let dependency_groups = pyproject.dependency_groups.get_dependencies(|_| true)?;
+            dependencies.extend(dependency_groups.into_values().flatten());
+        }
+    }
+
+    Ok(dependencies)
+}
+
+/// Reads `install_requires` from the `[options]` section and the `[options.extras_require]`
+/// section of the setup.cfg file
+fn get_dependencies_from_setup_cfg(
+    cfg_path: &PathBuf,
+    with_dev_deps: bool,
+) -> Result<HashSet<String>, Box<dyn Error>> {
+    let setup_cfg = parse_setup_cfg(&fs::read_to_string(cfg_path)?);
+
+    let mut requirements = SetupRequirements::default();
+    if let Some(value) = setup_cfg
+        .get("options")
+        .and_then(|o| o.get("install_requires"))
+    {
+        requirements.install_requires = cfg_list(value);
+    }
+    if let Some(extras) = setup_cfg.get("options.extras_require") {
+        for (extra, value) in extras.iter() {
+            requirements
+                .extras_require
+                .insert(extra.clone(), cfg_list(value));
+        }
+    }
+
+    requirements.get_dependencies(with_dev_deps)
+}
+
+/// Parses the INI format of setup.cfg into its sections and their options. Indented lines
+/// continue the value of the previous option
+fn parse_setup_cfg(content: &str) -> HashMap<String, HashMap<String, String>> {
+    let mut sections: HashMap<String, HashMap<String, String>> = HashMap::new();
+    let mut section = String::new();
+    let mut option: Option<String> = None;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with(';') {
+            continue;
+        }
+
+        if line.starts_with(char::is_whitespace) {
+            if let Some(value) = option
+                .as_ref()
+                .and_then(|option| sections.get_mut(&section)?.get_mut(option))
+            {
+                value.push('\n');
+                value.push_str(trimmed);
+            }
+        } else if let Some(name) = trimmed.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+            section = name.trim().to_string();
+            option = None;
+        } else if let Some((key, value)) = trimmed.split_once(['=', ':']) {
+            let key = key.trim().to_string();
+            sections
+                .entry(section.clone())
+                .or_default()
+                .insert(key.clone(), value.trim().to_string());
+            option = Some(key);
+        }
+    }
+
+    sections
+}
+
+/// Splits a setup.cfg option value into its list of non-empty lines
+fn cfg_list(value: &str) -> Vec<String> {
+    value
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Reads the `install_requires` and `extras_require` keyword arguments of the `setup()` call
+/// in setup.py. Only literal lists, tuples and dicts of strings are read, including when they
+/// are assigned to a module level variable, e.g. `install_requires=REQUIRES`
+fn get_dependencies_from_setup_py(
+    setup_py_path: &PathBuf,
+    with_dev_deps: bool,
+) -> Result<HashSet<String>, Box<dyn Error>> {
+    let setup_py_str = setup_py_path
+        .to_str()
+        .ok_or("Failed to convert path to string")?;
+    let python_source = fs::read_to_string(setup_py_path)?;
+    let python_statements = ast::Suite::parse(&python_source, setup_py_str)?;
+
+    let variables: HashMap<&str, &ast::Expr> = python_statements
+        .iter()
+        .filter_map(|statement| match statement {
+            ast::Stmt::Assign(assign) => match assign.targets.as_slice() {
+                [ast::Expr::Name(name)] => Some((name.id.as_str(), assign.value.as_ref())),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+
+    let mut requirements = SetupRequirements::default();
+    let Some(setup_call) = find_setup_call(&python_statements) else {
+        return Ok(HashSet::new());
+    };
+
+    for keyword in setup_call.keywords.iter() {
+        let Some(arg) = &keyword.arg else {
+            continue;
+        };
+        match arg.as_str() {
+            "install_requires" => {
+                requirements.install_requires = string_list(&keyword.value, &variables);
+            }
+            "extras_require" => {
+                if let ast::Expr::Dict(dict) = resolve(&keyword.value, &variables) {
+                    for (key, value) in dict.keys.iter().zip(dict.values.iter()) {
+                        if let Some(extra) = key.as_ref().and_then(string_constant) {
+                            requirements
+                                .extras_require
+                                .insert(extra, string_list(value, &variables));
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    requirements.get_dependencies(with_dev_deps)
+}
+
+/// Finds the `setup()` or `setuptools.setup()` call, which is either a module level statement
+/// or inside an `if __name__ == "__main__":` block
+fn find_setup_call(statements: &[ast::Stmt]) -> Option<&ast::ExprCall> {
+    statements.iter().find_map(|statement| match statement {
+        ast::Stmt::Expr(ast::StmtExpr { value, .. }) => match value.as_ref() {
+            ast::Expr::Call(call) => {
+                let name = match call.func.as_ref() {
+                    ast::Expr::Name(name) => name.id.as_str(),
+                    ast::Expr::Attribute(attribute) => attribute.attr.as_str(),
+                    _ => return None,
+                };
+                (name == "setup").then_some(call)
+            }
+            _ => None,
+        },
+        ast::Stmt::If(if_stmt) => {
+            find_setup_call(&if_stmt.body).or_else(|| find_setup_call(&if_stmt.orelse))
+        }
+        _ => None,
+    })
+}
+
+/// Resolves a variable to the literal assigned to it
+fn resolve<'a>(expr: &'a ast::Expr, variables: &HashMap<&str, &'a ast::Expr>) -> &'a ast::Expr {
+    match expr {
+        ast::Expr::Name(name) => variables.get(name.id.as_str()).copied().unwrap_or(expr),
+        _ => expr,
+    }
+}
+
+/// Returns the strings of a list or tuple literal, or of lists concatenated with `+`
+fn string_list(expr: &ast::Expr, variables: &HashMap<&str, &ast::Expr>) -> Vec<String> {
+    match resolve(expr, variables) {
+        ast::Expr::List(ast::ExprList { elts, .. })
+        | ast::Expr::Tuple(ast::ExprTuple { elts, .. }) => {
+            elts.iter().filter_map(string_constant).collect()
+        }
+        ast::Expr::BinOp(bin_op) if bin_op.op == ast::Operator::Add => {
+            let mut strings = string_list(&bin_op.left, variables);
+            strings.extend(string_list(&bin_op.right, variables));
+            strings
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn string_constant(expr: &ast::Expr) -> Option<String> {
+    match expr {
+        ast::Expr::Constant(ast::ExprConstant {
+            value: ast::Constant::Str(value),
+            ..
+        }) => Some(value.clone()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_dependencies_from_setup_py() {
+        let setup_py_path: PathBuf =
+            PathBuf::from("tests/fixtures/example_setuptools_project/setup.py");
+        let dependencies = get_dependencies_from_setup_py(&setup_py_path, false)
+            .expect("expected to get deps from setup.py");
+        let expected: HashSet<String> = [
+            "requests".to_string(),
+            "click".to_string(),
+            "pywin32".to_string(),
+            "tenacity".to_string(),
+            "redis".to_string(),
+            "PyYAML".to_string(),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+        assert_eq!(dependencies, expected);
+    }
+
+    #[test]
+    fn test_get_dependencies_from_setup_py_with_dev() {
+        let setup_py_path: PathBuf =
+            PathBuf::from("tests/fixtures/example_setuptools_project/setup.py");
+        let dependencies = get_dependencies_from_setup_py(&setup_py_path, true)
+            .expect("expected to get deps from setup.py");
+        assert_eq!(dependencies.len(), 8);
+        assert_eq!(dependencies.get("pytest"), Some(&"pytest".to_string()));
+    }
+
+    #[test]
+    fn test_get_dependencies_from_setup_cfg() {
+        let cfg_path: PathBuf = PathBuf::from("tests/fixtures/input/setup_cfg/setup.cfg");
+        let dependencies = get_dependencies_from_setup_cfg(&cfg_path, false)
+            .expect("expected to get deps from setup.cfg");
+        let expected: HashSet<String> = [
+            "requests".to_string(),
+            "sentry-sdk".to_string(),
+            "urllib3".to_string(),
+            "psycopg2-binary".to_string(),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+        assert_eq!(dependencies, expected);
+    }
+
+    #[test]
+    fn test_get_dependencies_from_setuptools_with_dev() {
+        let project_path = Path::new("tests/fixtures/input/setup_cfg");
+        let dependencies = get_dependencies_from_setuptools(project_path, true)
+            .expect("expected to get deps from setup.cfg and setup.py");
+        assert_eq!(dependencies.len(), 6);
+        assert_eq!(
+            dependencies.get("pytest-cov"),
+            Some(&"pytest-cov".to_string())
+        );
+    }
+}
@@ -0,0 +1,138 @@
+//! Test paths tell the test code of a project apart from its production code, so the imports of
+//! each can be matched with the main and dev dependencies.
+use std::path::Path;
+
+/// The default test paths: test directories, pytest's conftest.py and test modules
+pub const DEFAULT_TEST_PATHS: [&str; 5] =
+    ["tests/", "test/", "conftest.py", "test_*.py", "*_test.py"];
+
+/// The patterns of the test files of a project, relative to the project path. A pattern ending
+/// with `/` matches a directory at any depth, including a path of directories such as
+/// `tests/unit/`, and any other pattern matches a file name, where a `*` matches any characters
+#[derive(Clone, Debug)]
+pub struct TestPaths {
+    patterns: Vec<String>,
+}
+
+impl Default for TestPaths {
+    fn default() -> Self {
+        Self::new(DEFAULT_TEST_PATHS.iter().map(|p| p.to_string()).collect())
+    }
+}
+
+impl TestPaths {
+    pub fn new(patterns: Vec<String>) -> Self {
+        Self { patterns }
+    }
+
+    /// Whether the file, relative to the project path, is test code
+    pub fn is_test_file(&self, path: &Path) -> bool {
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+        let directories: Vec<String> = path
+            .parent()
+            .into_iter()
+            .flat_map(Path::components)
+            .map(|component| component.as_os_str().to_string_lossy().to_string())
+            .collect();
+
+        self.patterns
+            .iter()
+            .any(|pattern| match pattern.strip_suffix('/') {
+                Some(directory) => matches_directories(directory, &directories),
+                None => matches_glob(pattern, &file_name),
+            })
+    }
+}
+
+/// Whether the directories of a path contain the directories of the pattern, in a row, e.g.
+/// `tests/unit` for `src/tests/unit/models`
+fn matches_directories(pattern: &str, directories: &[String]) -> bool {
+    let segments: Vec<&str> = pattern
+        .split('/')
+        .filter(|s| !s.is_empty() && *s != ".")
+        .collect();
+    if segments.is_empty() {
+        return false;
+    }
+    directories.windows(segments.len()).any(|window| {
+        window
+            .iter()
+            .zip(segments.iter())
+            .all(|(directory, segment)| matches_glob(segment, directory))
+    })
+}
+
+/// Matches a name with a pattern where `*` matches any characters, e.g. `test_*.py`
+fn matches_glob(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_test_file() {
+        let test_paths = TestPaths::default();
+        assert!(test_paths.is_test_file(Path::new("tests/test_app.py")));
+        assert!(test_paths.is_test_file(Path::new("src/app/tests/helpers.py")));
+        assert!(test_paths.is_test_file(Path::new("conftest.py")));
+        assert!(test_paths.is_test_file(Path::new("src/app/client_test.py")));
+        assert!(test_paths.is_test_file(Path::new("src/app/test_client.py")));
+        assert!(!test_paths.is_test_file(Path::new("src/app/client.py")));
+        assert!(!test_paths.is_test_file(Path::new("src/app/testing.py")));
+    }
+
+    #[test]
+    fn test_is_test_file_with_custom_patterns() {
+        let test_paths = TestPaths::new(vec!["spec/".to_string(), "*_spec.py".to_string()]);
+        assert!(test_paths.is_test_file(Path::new("spec/app.py")));
+        assert!(test_paths.is_test_file(Path::new("app/client_spec.py")));
+        assert!(!test_paths.is_test_file(Path::new("tests/test_app.py")));
+    }
+
+    #[test]
+    fn test_is_test_file_with_nested_directory_patterns() {
+        let test_paths = TestPaths::new(vec![
+            "tests/unit/".to_string(),
+            "src/app/tests/".to_string(),
+        ]);
+        assert!(test_paths.is_test_file(Path::new("tests/unit/test_models.py")));
+        assert!(test_paths.is_test_file(Path::new("tests/unit/models/helpers.py")));
+        assert!(test_paths.is_test_file(Path::new("src/app/tests/helpers.py")));
+        assert!(test_paths.is_test_file(Path::new("packages/api/tests/unit/conftest.py")));
+        assert!(!test_paths.is_test_file(Path::new("tests/integration/test_api.py")));
+        assert!(!test_paths.is_test_file(Path::new("unit/tests/test_models.py")));
+        assert!(!test_paths.is_test_file(Path::new("src/app/client.py")));
+    }
+
+    #[test]
+    fn test_matches_glob() {
+        assert!(matches_glob("test_*.py", "test_app.py"));
+        assert!(matches_glob("*", "anything"));
+        assert!(matches_glob("a*b*c", "aXbYc"));
+        assert!(!matches_glob("test_*.py", "test_app.pyc"));
+        assert!(!matches_glob("*_test.py", "_test.p"));
+        assert!(matches_glob("conftest.py", "conftest.py"));
+        assert!(!matches_glob("conftest.py", "conftest.pyi"));
+    }
+}
@@ -0,0 +1,227 @@
+//! uv is a Python package and project manager.
+//! This module parses uv pyproject.toml and lock files to get package dependencies.
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fs,
+    io::Read,
+    path::Path,
+    string::String,
+};
+
+extern crate toml;
+use serde::Deserialize;
+
+use crate::{
+    domain::{GroupSelection, DEV_GROUP},
+    formatters::normalize_package_name,
+    pep508::parse_requirement,
+    pyproject::PyProjectToml,
+};
+
+#[derive(Deserialize, Debug)]
+struct Tool {
+    uv: Option<Uv>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Uv {
+    #[serde(rename = "dev-dependencies")]
+    dev_dependencies: Option<Vec<String>>,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+struct UvLock {
+    #[serde(default)]
+    package: Vec<Package>,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+struct Package {
+    name: String,
+    #[serde(default)]
+    dependencies: Vec<Dependency>,
+    #[serde(rename = "optional-dependencies", default)]
+    optional_dependencies: HashMap<String, Vec<Dependency>>,
+    #[serde(rename = "dev-dependencies", default)]
+    dev_dependencies: HashMap<String, Vec<Dependency>>,
+}
+
+/// A dependency edge in the lock file, e.g. `{ name = "pydantic", extra = ["email"] }`
+#[derive(Clone, Deserialize, Debug)]
+struct Dependency {
+    name: String,
+    #[serde(default)]
+    extra: Vec<String>,
+}
+
+/// Reads the pyproject.toml file and returns the dependencies keyed by group. The `[project]`
+/// dependencies are in the main group, and the selected `[dependency-groups]` are dev
+/// dependency groups. The legacy `[tool.uv].dev-dependencies` list is in the `dev` group
+pub fn get_dependencies_from_pyproject(
+    toml_file_path: &Path,
+    selection: &GroupSelection,
+) -> Result<HashMap<String, HashSet<String>>, Box<dyn Error>> {
+    let pyproject: PyProjectToml<Tool> = PyProjectToml::read(toml_file_path)?;
+    let mut groups = pyproject.get_dependencies(selection)?;
+
+    let dev_dependencies = pyproject.tool.and_then(|tool| tool.uv?.dev_dependencies);
+    if let Some(dev_dependencies) = dev_dependencies {
+        if selection.includes(DEV_GROUP, false) {
+            let dev_group = groups.entry(DEV_GROUP.to_string()).or_default();
+            for spec in dev_dependencies.iter() {
+                dev_group.insert(parse_requirement(spec)?.name);
+            }
+        }
+    }
+
+    Ok(groups)
+}
+
+/// Checks the lock file for any package extras and returns a HashMap
+/// with the package name as the key and a Vec of the extras as the value.
+/// Only the extras requested by a dependency edge in the lock file are considered,
+/// e.g. `{ name = "pydantic", extra = ["email"] }`
+pub fn check_lock_file_for_package_extras(
+    project_path: &Path,
+    manifest_packages: &HashSet<String>,
+    verbose: bool,
+) -> Result<HashMap<String, Vec<String>>, Box<dyn Error>> {
+    let lock_file_path = project_path.join("uv.lock");
+    if !lock_file_path.exists() {
+        eprintln!("WARNING: Project has no lock file.");
+        return Ok(HashMap::new());
+    }
+
+    let mut lock_file_content: String = String::new();
+    fs::File::open(lock_file_path)?.read_to_string(&mut lock_file_content)?;
+    let lock_file: UvLock = toml::from_str(&lock_file_content)?;
+
+    let manifest_packages: HashSet<String> = manifest_packages
+        .iter()
+        .map(|pkg| normalize_package_name(pkg))
+        .collect();
+
+    let mut requested_extras: HashMap<&str, HashSet<&str>> = HashMap::new();
+    for package in lock_file.package.iter() {
+        let edges = package
+            .dependencies
+            .iter()
+            .chain(package.optional_dependencies.values().flatten())
+            .chain(package.dev_dependencies.values().flatten());
+        for edge in edges {
+            requested_extras
+                .entry(edge.name.as_str())
+                .or_default()
+                .extend(edge.extra.iter().map(String::as_str));
+        }
+    }
+
+    let mut extras: HashMap<String, Vec<String>> = HashMap::new();
+    for package in lock_file.package.iter() {
+        let Some(requested) = requested_extras.get(package.name.as_str()) else {
+            continue;
+        };
+        for (extra, dependencies) in package.optional_dependencies.iter() {
+            if !requested.contains(extra.as_str()) {
+                continue;
+            }
+            for dependency in dependencies.iter() {
+                if manifest_packages.contains(&dependency.name) {
+                    if verbose {
+                        eprintln!(
+                            "Found {} - it is an extra dependency of {}",
+                            dependency.name, package.name
+                        );
+                    }
+                    extras
+                        .entry(package.name.clone())
+                        .or_default()
+                        .push(dependency.name.clone());
+                }
+            }
+        }
+    }
+
+    Ok(extras)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::MAIN_GROUP;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_get_dependencies_from_pyproject() {
+        let toml_file_path: PathBuf =
+            PathBuf::from("tests/fixtures/example_uv_project/pyproject.toml");
+        let groups = get_dependencies_from_pyproject(&toml_file_path, &GroupSelection::default())
+            .expect("expected to get deps from manifest");
+        assert_eq!(groups.len(), 1);
+        let dependencies = &groups[MAIN_GROUP];
+        let expected: HashSet<String> = [
+            "fastapi".to_string(),
+            "pydantic".to_string(),
+            "email-validator".to_string(),
+            "redis".to_string(),
+            "requests".to_string(),
+            "sentry-sdk".to_string(),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+        assert_eq!(dependencies, &expected);
+    }
+
+    #[test]
+    fn test_get_dependencies_from_pyproject_with_dev() {
+        let toml_file_path: PathBuf =
+            PathBuf::from("tests/fixtures/example_uv_project/pyproject.toml");
+        let selection = GroupSelection {
+            dev: true,
+            ..Default::default()
+        };
+        let groups = get_dependencies_from_pyproject(&toml_file_path, &selection)
+            .expect("expected to get deps from manifest");
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[MAIN_GROUP].len(), 6);
+        assert_eq!(
+            groups.get("dev"),
+            Some(&["pytest".to_string(), "ruff".to_string()].into())
+        );
+        assert_eq!(groups.get("lint"), Some(&["mypy".to_string()].into()));
+    }
+
+    #[test]
+    fn test_check_lock_file_for_package_extras() {
+        let project_path: PathBuf = PathBuf::from("tests/fixtures/example_uv_project");
+        let manifest_packages: HashSet<String> = [
+            "fastapi".to_string(),
+            "pydantic".to_string(),
+            "Email_Validator".to_string(),
+            "redis".to_string(),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+
+        let extras = check_lock_file_for_package_extras(&project_path, &manifest_packages, false)
+            .expect("failed to parse lock file");
+
+        // fastapi[all] is not requested so email-validator is only an extra of pydantic
+        assert_eq!(extras.len(), 1);
+        assert_eq!(
+            extras.get("pydantic"),
+            Some(&vec!["email-validator".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_check_lock_file_for_package_extras_malformed_lockfile() {
+        let project_path = PathBuf::from("tests/fixtures/input/malformed_uv_lockfile");
+        let manifest_packages: HashSet<String> = ["pydantic".to_string()].iter().cloned().collect();
+        let extras = check_lock_file_for_package_extras(&project_path, &manifest_packages, false);
+        assert!(extras.is_err());
+    }
+}
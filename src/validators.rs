@@ -4,9 +4,22 @@ use std::{
 };
 
 const INVALID_PATH: &str = "Invalid path provided";
-const MISSING_PYPROJECT_TOML: &str = "Unable to find a pyproject.toml file";
+const MISSING_PYPROJECT_TOML: &str =
+    "Unable to find a pyproject.toml, Pipfile, setup.py, setup.cfg, environment.yml, requirements.txt or requirements.in file";
 const NON_SUPPORTED_TOOLING: &str =
-    "This does not appear to be a Poetry project (no poetry.lock file). Only Poetry is supported at this time. Isn't Python packaging fun? :)";
+    "This does not appear to be a supported project. Only Poetry (poetry.lock), uv (uv.lock), PDM (pdm.lock), Hatch (hatch.toml or [tool.hatch.envs]), Pipenv (Pipfile), setuptools (setup.py or setup.cfg), Conda (environment.yml) and pip (requirements.txt) are supported at this time, see --manager. Isn't Python packaging fun? :)";
+
+/// The files that declare a project's dependencies
+const MANIFEST_FILES: [&str; 8] = [
+    "pyproject.toml",
+    "Pipfile",
+    "setup.py",
+    "setup.cfg",
+    "environment.yml",
+    "environment.yaml",
+    "requirements.txt",
+    "requirements.in",
+];
 
 #[derive(Debug)]
 pub enum PathError {
@@ -39,8 +52,7 @@ impl error::Error for PathError {}
 
 /// Validates the path provided by the user:
 /// - Checks the path exists
-/// - Checks the path contains a pyproject.toml file
-/// - Checks the path is a Poetry project (has a poetry.lock file)
+/// - Checks the path contains a pyproject.toml, Pipfile, setuptools, conda or requirements file
 pub fn valid_python_path(source_code_path: &String) -> Result<PathBuf, PathError> {
     let valid_path = Path::new(&source_code_path);
     let ok_path = provided_path(valid_path)?;
@@ -48,16 +60,11 @@ pub fn valid_python_path(source_code_path: &String) -> Result<PathBuf, PathError
         return Err(PathError::InvalidPath);
     }
 
-    let ok_toml_file = check_pyproject_file_exists(valid_path)?;
-    if !ok_toml_file {
+    let ok_manifest_file = check_manifest_file_exists(valid_path)?;
+    if !ok_manifest_file {
         return Err(PathError::MissingPyprojectToml);
     }
 
-    let ok_poetry_project = check_is_poetry_project(valid_path)?;
-    if !ok_poetry_project {
-        return Err(PathError::NonSupportedTooling);
-    }
-
     let path_buf = valid_path.to_path_buf();
     Ok(path_buf)
 }
@@ -69,18 +76,12 @@ fn provided_path(source_code_path: &Path) -> Result<bool, PathError> {
     }
 }
 
-fn check_pyproject_file_exists(source_code_path: &Path) -> Result<bool, PathError> {
-    let toml_file_path: PathBuf = Path::new(&source_code_path).join("pyproject.toml");
-    match fs::metadata(toml_file_path) {
-        Ok(_) => Ok(true),
-        Err(_) => Err(PathError::MissingPyprojectToml),
-    }
-}
-
-fn check_is_poetry_project(source_code_path: &Path) -> Result<bool, PathError> {
-    let lock_file_path: PathBuf = Path::new(&source_code_path).join("poetry.lock");
-    match fs::metadata(lock_file_path) {
-        Ok(_) => Ok(true),
-        Err(_) => Err(PathError::NonSupportedTooling),
+fn check_manifest_file_exists(source_code_path: &Path) -> Result<bool, PathError> {
+    let exists = MANIFEST_FILES
+        .iter()
+        .any(|file_name| fs::metadata(source_code_path.join(file_name)).is_ok());
+    match exists {
+        true => Ok(true),
+        false => Err(PathError::MissingPyprojectToml),
     }
 }
@@ -0,0 +1,271 @@
+//! A virtualenv has the metadata of each installed distribution in a `*.dist-info` directory.
+//! This module reads the `top_level.txt` and `RECORD` files to get the top level modules each
+//! installed distribution provides, so packages are matched with imports without guessing.
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::formatters::normalize_package_name;
+
+/// The virtualenv directories of a project, in order of preference
+const VENV_DIRS: [&str; 2] = [".venv", "venv"];
+
+/// Returns the path of the virtualenv in the project directory, if there is one
+pub fn find_project_venv(project_path: &Path) -> Option<PathBuf> {
+    VENV_DIRS
+        .iter()
+        .map(|dir| project_path.join(dir))
+        .find(|venv_path| find_site_packages(venv_path).is_some())
+}
+
+/// Reads the metadata of the distributions installed in the virtualenv and returns their
+/// modules, keyed by the normalized distribution name, for example:
+/// ```ignore
+///  {
+///     "protobuf": ["google.protobuf"],
+///     "six": ["six"],
+/// }
+/// ```
+pub fn get_installed_imports(
+    venv_path: &Path,
+) -> Result<HashMap<String, Vec<String>>, Box<dyn Error>> {
+    let site_packages = find_site_packages(venv_path).ok_or(format!(
+        "Unable to find the site-packages of the virtualenv {}",
+        venv_path.display()
+    ))?;
+
+    let mut installed_imports = HashMap::new();
+    for entry in fs::read_dir(site_packages)? {
+        let dist_info = entry?.path();
+        let Some(dir_name) = dist_info.file_name().map(|name| name.to_string_lossy()) else {
+            continue;
+        };
+        let Some(name_version) = dir_name.strip_suffix(".dist-info") else {
+            continue;
+        };
+
+        let name = get_distribution_name(&dist_info).unwrap_or_else(|| {
+            name_version
+                .split('-')
+                .next()
+                .unwrap_or_default()
+                .to_string()
+        });
+        let imports = get_top_level_modules(&dist_info)?;
+        if !imports.is_empty() {
+            installed_imports.insert(normalize_package_name(&name), imports);
+        }
+    }
+
+    Ok(installed_imports)
+}
+
+/// Finds the site-packages directory, `lib/python3.X/site-packages` on Unix and
+/// `Lib/site-packages` on Windows
+fn find_site_packages(venv_path: &Path) -> Option<PathBuf> {
+    let windows_site_packages = venv_path.join("Lib").join("site-packages");
+    if windows_site_packages.is_dir() {
+        return Some(windows_site_packages);
+    }
+
+    fs::read_dir(venv_path.join("lib"))
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with("python"))
+        })
+        .map(|path| path.join("site-packages"))
+        .find(|path| path.is_dir())
+}
+
+/// Reads the distribution name from the `Name:` field of the METADATA file, as the directory
+/// name may be normalized differently
+fn get_distribution_name(dist_info: &Path) -> Option<String> {
+    let metadata = fs::read_to_string(dist_info.join("METADATA")).ok()?;
+    metadata
+        .lines()
+        .take_while(|line| !line.is_empty())
+        .find_map(|line| line.strip_prefix("Name:"))
+        .map(|name| name.trim().to_string())
+}
+
+/// Reads the modules from the files installed by the distribution listed in RECORD or, when
+/// there is no RECORD, from top_level.txt. The modules of a namespace package are its
+/// subpackages, e.g. `google.protobuf` for protobuf, as `google` is shared with other
+/// distributions
+fn get_top_level_modules(dist_info: &Path) -> Result<Vec<String>, Box<dyn Error>> {
+    let record_path = dist_info.join("RECORD");
+    if record_path.is_file() {
+        let record = fs::read_to_string(record_path)?;
+        let files: HashSet<&str> = record
+            .lines()
+            .filter_map(|line| line.split(',').next())
+            .map(|file| file.trim_matches('"'))
+            .collect();
+        let modules: BTreeSet<String> = files
+            .iter()
+            .filter_map(|file| record_module(file, &files))
+            .collect();
+        return Ok(modules.into_iter().collect());
+    }
+
+    let top_level_path = dist_info.join("top_level.txt");
+    if !top_level_path.is_file() {
+        return Ok(Vec::new());
+    }
+    let modules: BTreeSet<String> = fs::read_to_string(top_level_path)?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        // top_level.txt may list nested packages, e.g. `google/protobuf`
+        .map(|line| line.replace('/', "."))
+        .collect();
+
+    Ok(modules.into_iter().collect())
+}
+
+/// Returns the module of a file listed in RECORD, e.g. `six` for `six.py` or `yaml` for
+/// `yaml/__init__.py`. Directories without an `__init__.py` are namespace packages, so the
+/// module is the first package below them, e.g. `google.cloud.storage` for
+/// `google/cloud/storage/blob.py`. Metadata, scripts and `.pth` files are not modules
+fn record_module(file: &str, files: &HashSet<&str>) -> Option<String> {
+    let segments: Vec<&str> = file.split('/').collect();
+    let first = segments[0];
+    if first.is_empty()
+        || first == ".."
+        || first == "__pycache__"
+        || first.ends_with(".dist-info")
+        || first.ends_with(".data")
+    {
+        return None;
+    }
+
+    let (file_name, directories) = segments.split_last()?;
+    for depth in 1..=directories.len() {
+        let package = &directories[..depth];
+        if files.contains(format!("{}/__init__.py", package.join("/")).as_str()) {
+            return Some(package.join("."));
+        }
+    }
+
+    let (module, extension) = file_name.split_once('.')?;
+    if !matches!(extension.rsplit('.').next(), Some("py" | "so" | "pyd"))
+        || directories.contains(&"__pycache__")
+    {
+        return None;
+    }
+    let mut path = directories.to_vec();
+    path.push(module);
+    Some(path.join("."))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_installed_imports() {
+        let venv_path = Path::new("tests/fixtures/input/venv_project/.venv");
+        let installed_imports =
+            get_installed_imports(venv_path).expect("expected to read the virtualenv");
+        assert_eq!(installed_imports.len(), 4);
+        assert_eq!(
+            installed_imports.get("protobuf"),
+            Some(&vec!["google".to_string()])
+        );
+        assert_eq!(installed_imports.get("six"), Some(&vec!["six".to_string()]));
+        assert_eq!(
+            installed_imports.get("acme-platform-client"),
+            Some(&vec!["acme_client".to_string()])
+        );
+        assert_eq!(
+            installed_imports.get("pyyaml"),
+            Some(&vec!["_yaml".to_string(), "yaml".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_get_installed_imports_of_namespace_packages() {
+        let venv_path = Path::new("tests/fixtures/input/venv_namespace_packages/.venv");
+        let installed_imports =
+            get_installed_imports(venv_path).expect("expected to read the virtualenv");
+        assert_eq!(
+            installed_imports.get("protobuf"),
+            Some(&vec![
+                "google._upb._message".to_string(),
+                "google.protobuf".to_string()
+            ])
+        );
+        assert_eq!(
+            installed_imports.get("google-cloud-storage"),
+            Some(&vec!["google.cloud.storage".to_string()])
+        );
+        assert_eq!(
+            installed_imports.get("google-api-core"),
+            Some(&vec!["google.api_core".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_get_installed_imports_without_site_packages() {
+        let venv_path = Path::new("tests/fixtures/input/venv_project");
+        assert!(get_installed_imports(venv_path).is_err());
+    }
+
+    #[test]
+    fn test_find_project_venv() {
+        let project_path = Path::new("tests/fixtures/input/venv_project");
+        assert_eq!(
+            find_project_venv(project_path),
+            Some(project_path.join(".venv"))
+        );
+        assert_eq!(
+            find_project_venv(Path::new("tests/fixtures/example_project")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_record_module() {
+        let files = HashSet::from([
+            "six.py",
+            "yaml/__init__.py",
+            "yaml/composer.py",
+            "_cffi_backend.cpython-311-x86_64-linux-gnu.so",
+            "google/cloud/storage/__init__.py",
+            "google/cloud/storage/blob.py",
+            "google/cloud/storage/__pycache__/blob.cpython-311.pyc",
+        ]);
+        assert_eq!(record_module("six.py", &files), Some("six".to_string()));
+        assert_eq!(
+            record_module("yaml/composer.py", &files),
+            Some("yaml".to_string())
+        );
+        assert_eq!(
+            record_module("_cffi_backend.cpython-311-x86_64-linux-gnu.so", &files),
+            Some("_cffi_backend".to_string())
+        );
+        assert_eq!(
+            record_module("google/cloud/storage/blob.py", &files),
+            Some("google.cloud.storage".to_string())
+        );
+        assert_eq!(
+            record_module(
+                "google/cloud/storage/__pycache__/blob.cpython-311.pyc",
+                &files
+            ),
+            Some("google.cloud.storage".to_string())
+        );
+        assert_eq!(record_module("six-1.16.0.dist-info/RECORD", &files), None);
+        assert_eq!(record_module("../../../bin/normalizer", &files), None);
+        assert_eq!(
+            record_module("__pycache__/six.cpython-311.pyc", &files),
+            None
+        );
+        assert_eq!(record_module("distutils-precedence.pth", &files), None);
+    }
+}
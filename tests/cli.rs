@@ -16,6 +16,311 @@ fn runs_with_success() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn runs_with_success_uv_project() -> TestResult {
+    Command::cargo_bin(APP)?
+        .arg("./tests/fixtures/example_uv_project")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "email-validator - an extra of pydantic",
+        ));
+    Ok(())
+}
+
+#[test]
+fn runs_with_success_pdm_project() -> TestResult {
+    Command::cargo_bin(APP)?
+        .arg("./tests/fixtures/example_pdm_project")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "email-validator - an extra of pydantic",
+        ));
+    Ok(())
+}
+
+#[test]
+fn runs_with_success_requirements_project() -> TestResult {
+    Command::cargo_bin(APP)?
+        .arg("./tests/fixtures/example_requirements_project")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("pywin32"));
+    Ok(())
+}
+
+#[test]
+fn runs_with_success_pipenv_project() -> TestResult {
+    Command::cargo_bin(APP)?
+        .arg("./tests/fixtures/example_pipenv_project")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("email_validator"));
+    Ok(())
+}
+
+#[test]
+fn runs_with_success_setuptools_project() -> TestResult {
+    Command::cargo_bin(APP)?
+        .arg("./tests/fixtures/example_setuptools_project")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("tenacity"));
+    Ok(())
+}
+
+#[test]
+fn runs_with_success_conda_project() -> TestResult {
+    Command::cargo_bin(APP)?
+        .arg("./tests/fixtures/example_conda_project")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("lightgbm"))
+        .stdout(predicate::str::contains("cudatoolkit").not())
+        .stdout(predicate::str::contains("pytorch").not());
+    Ok(())
+}
+
+#[test]
+fn runs_with_success_hatch_project_reports_per_environment() -> TestResult {
+    Command::cargo_bin(APP)?
+        .args(["./tests/fixtures/example_hatch_project", "--dev"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Possible unused manifest packages: \nredis\n",
+        ))
+        .stdout(predicate::str::contains(
+            "Possible unused packages in `lint`: \nmypy\nruff\n",
+        ));
+    Ok(())
+}
+
+#[test]
+fn runs_with_success_poetry_project_reports_per_group() -> TestResult {
+    Command::cargo_bin(APP)?
+        .args(["./tests/fixtures/input/poetry_groups", "--group", "docs"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Possible unused packages in `docs`: \nmkdocs\n",
+        ))
+        .stdout(predicate::str::contains("`test`").not());
+    Command::cargo_bin(APP)?
+        .args(["./tests/fixtures/input/poetry_groups", "--all-groups"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Possible unused packages in `test`: \npytest_cov\n",
+        ))
+        .stdout(predicate::str::contains("`docs`"));
+    Ok(())
+}
+
+#[test]
+fn runs_with_warning_unknown_dependency_group() -> TestResult {
+    for project in [
+        "./tests/fixtures/example_uv_project",
+        "./tests/fixtures/example_pdm_project",
+        "./tests/fixtures/example_hatch_project",
+    ] {
+        Command::cargo_bin(APP)?
+            .args([project, "--group", "lnit"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "WARNING: Dependency group `lnit` not found.",
+            ));
+    }
+    Ok(())
+}
+
+#[test]
+fn runs_with_warning_unknown_pep_735_dependency_group() -> TestResult {
+    Command::cargo_bin(APP)?
+        .args([
+            "./tests/fixtures/input/dependency_groups",
+            "--group",
+            "tset",
+            "--group",
+            "typing",
+        ])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "WARNING: Dependency group `tset` not found.",
+        ))
+        .stderr(predicate::str::contains("`typing` not found").not())
+        .stdout(predicate::str::contains(
+            "Possible unused packages in `typing`: \nmypy\n",
+        ));
+    Ok(())
+}
+
+#[test]
+fn runs_with_expected_error_dependency_group_cycle() -> TestResult {
+    Command::cargo_bin(APP)?
+        .args(["./tests/fixtures/input/dependency_groups_cycle", "--dev"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Dependency groups include a cycle",
+        ));
+    Ok(())
+}
+
+#[test]
+fn runs_with_success_reports_missing_packages() -> TestResult {
+    Command::cargo_bin(APP)?
+        .arg("./tests/fixtures/input/missing_imports")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Possible missing manifest packages: \nurllib3 - imported in app/client.py:3\nyaml - imported in app/client.py:2, app/settings.py:3\n",
+        ));
+    Ok(())
+}
+
+#[test]
+fn runs_with_success_reports_transitive_packages() -> TestResult {
+    Command::cargo_bin(APP)?
+        .arg("./tests/fixtures/input/transitive")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "sniffio - installed by fastapi -> anyio -> sniffio, imported in app/main.py:1\n",
+        ));
+    Ok(())
+}
+
+#[test]
+fn runs_with_success_ignores_first_party_modules() -> TestResult {
+    Command::cargo_bin(APP)?
+        .arg("tests/fixtures/input/first_party")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Possible unused manifest packages: \nmarkdown\n",
+        ))
+        .stdout(predicate::str::contains("missing").not());
+    Ok(())
+}
+
+#[test]
+fn runs_with_success_reports_misplaced_packages() -> TestResult {
+    Command::cargo_bin(APP)?
+        .arg("./tests/fixtures/input/misplaced")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Possible misplaced packages: \nfreezegun - a main dependency only imported in conftest.py:1, tests/test_cli.py:2\nrich - a dev dependency imported in app/cli.py:2\n",
+        ));
+    Command::cargo_bin(APP)?
+        .args([
+            "./tests/fixtures/input/misplaced",
+            "--test-path",
+            "conftest.py",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "pytest - a dev dependency imported in tests/test_cli.py",
+        ));
+    Ok(())
+}
+
+#[test]
+fn runs_with_success_venv_metadata() -> TestResult {
+    Command::cargo_bin(APP)?
+        .args([
+            "./tests/fixtures/input/venv_project",
+            "--venv",
+            "./tests/fixtures/input/venv_project/.venv",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("six"))
+        .stdout(predicate::str::contains("acme").not());
+    Ok(())
+}
+
+#[test]
+fn runs_with_expected_error_missing_venv() -> TestResult {
+    Command::cargo_bin(APP)?
+        .args(["./tests/fixtures/example_project", "--venv", "./no_venv"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Unable to find the site-packages of the virtualenv",
+        ));
+    Ok(())
+}
+
+#[test]
+fn runs_with_warning_several_package_managers() -> TestResult {
+    Command::cargo_bin(APP)?
+        .arg("tests/fixtures/input/several_managers")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "WARNING: Found project files for several package managers: Poetry (poetry.lock, [tool.poetry], build-backend poetry.core.masonry.api), uv (uv.lock).",
+        ))
+        .stderr(predicate::str::contains("Using Poetry"));
+    Ok(())
+}
+
+#[test]
+fn runs_with_warning_when_evidence_is_tied() -> TestResult {
+    Command::cargo_bin(APP)?
+        .arg("tests/fixtures/input/setup_py_requirements")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "WARNING: Found project files for several package managers: setuptools (setup.py), pip (requirements.txt, requirements-dev.txt).",
+        ))
+        .stderr(predicate::str::contains(
+            "Using setuptools as its evidence is the strongest",
+        ))
+        .stdout(predicate::str::contains("tenacity"));
+    Ok(())
+}
+
+#[test]
+fn runs_with_warning_when_tie_is_broken_by_order() -> TestResult {
+    Command::cargo_bin(APP)?
+        .arg("tests/fixtures/input/setuptools_requirements")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "WARNING: Found project files for several package managers: pip (requirements.txt), setuptools (build-backend setuptools.build_meta).",
+        ))
+        .stderr(predicate::str::contains(
+            "Using pip as the evidence is tied and it comes first in the --manager values",
+        ))
+        .stdout(predicate::str::contains("redis").not());
+    Command::cargo_bin(APP)?
+        .args([
+            "tests/fixtures/input/setuptools_requirements",
+            "--manager",
+            "setuptools",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("redis"));
+    Ok(())
+}
+
+#[test]
+fn runs_with_manager_override() -> TestResult {
+    Command::cargo_bin(APP)?
+        .args(["tests/fixtures/input/several_managers", "--manager", "uv"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("WARNING: Found project files").not());
+    Ok(())
+}
+
 #[test]
 fn runs_with_expected_error_no_pyproject_file() -> TestResult {
     Command::cargo_bin(APP)?
@@ -47,3 +352,183 @@ fn runs_with_expected_error_not_poetry_project() -> TestResult {
         .stderr(predicate::str::contains(NonSupportedTooling));
     Ok(())
 }
+
+#[test]
+fn runs_with_success_config_from_pyproject() -> TestResult {
+    Command::cargo_bin(APP)?
+        .arg("tests/fixtures/input/config_pyproject")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No unused packages found."))
+        .stdout(predicate::str::contains("boto3").not())
+        .stdout(predicate::str::contains("yaml").not());
+    Ok(())
+}
+
+#[test]
+fn runs_with_no_dev_overriding_config() -> TestResult {
+    Command::cargo_bin(APP)?
+        .args([
+            "tests/fixtures/input/config_pyproject",
+            "--src",
+            ".",
+            "--exclude",
+            "tests",
+            "--no-dev",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("pytest").not());
+    Ok(())
+}
+
+#[test]
+fn runs_with_success_json_format_and_verbose() -> TestResult {
+    let output = Command::cargo_bin(APP)?
+        .args([
+            "tests/fixtures/example_project_2",
+            "--format",
+            "json",
+            "--verbose",
+        ])
+        .output()?;
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let unused = report["unused"]["main"]
+        .as_array()
+        .ok_or("expected the unused main packages")?;
+    assert!(unused.contains(&serde_json::json!({
+        "package": "email-validator",
+        "extra_of": "pydantic",
+    })));
+    assert!(unused.contains(&serde_json::json!({
+        "package": "requests",
+        "extra_of": null,
+    })));
+    assert!(String::from_utf8(output.stderr)?.contains("extra dependency"));
+    Ok(())
+}
+
+#[test]
+fn runs_with_success_config_overridden_by_flags() -> TestResult {
+    Command::cargo_bin(APP)?
+        .args([
+            "tests/fixtures/input/config_pyproject",
+            "--src",
+            ".",
+            "--exclude",
+            "tests",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "yaml - imported in setup_helper.py",
+        ))
+        .stdout(predicate::str::contains("pytest"))
+        .stdout(predicate::str::contains("boto3").not());
+    Ok(())
+}
+
+#[test]
+fn runs_with_success_json_format_from_deptr_toml() -> TestResult {
+    let output = Command::cargo_bin(APP)?
+        .arg("tests/fixtures/input/config_file")
+        .output()?;
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(
+        report["missing"]["yaml"],
+        serde_json::json!([{ "file": "app/__init__.py", "line": 3, "column": 1 }])
+    );
+    assert_eq!(report["unused"], serde_json::json!({}));
+    Ok(())
+}
+
+#[test]
+fn runs_with_success_text_format_flag() -> TestResult {
+    Command::cargo_bin(APP)?
+        .args(["tests/fixtures/input/config_file", "--format", "text"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "yaml - imported in app/__init__.py",
+        ));
+    Ok(())
+}
+
+#[test]
+fn runs_with_success_ignore_reasons_and_stale_ignores() -> TestResult {
+    Command::cargo_bin(APP)?
+        .args([
+            "tests/fixtures/input/config_pyproject",
+            "--verbose",
+            "--ignore",
+            "flask",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Ignoring uvicorn - started by the Procfile",
+        ))
+        .stdout(predicate::str::contains(
+            "Stale ignores, these packages are not reported and can be removed: \nflask",
+        ))
+        .stdout(predicate::str::contains("gunicorn").not());
+    Ok(())
+}
+
+#[test]
+fn runs_with_success_inline_ignores() -> TestResult {
+    Command::cargo_bin(APP)?
+        .args(["tests/fixtures/input/inline_ignores", "--all-groups"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("tenacity"))
+        .stdout(predicate::str::contains("uvicorn").not())
+        .stdout(predicate::str::contains("gunicorn").not())
+        .stdout(predicate::str::contains("dotenv").not())
+        .stdout(predicate::str::contains("pytest_cov").not())
+        .stdout(predicate::str::contains("mkdocs").not())
+        .stdout(predicate::str::contains("Stale ignores").not());
+    Ok(())
+}
+
+#[test]
+fn runs_with_success_nested_imports() -> TestResult {
+    Command::cargo_bin(APP)?
+        .arg("tests/fixtures/input/nested_imports")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No unused packages found."))
+        .stdout(predicate::str::contains(
+            "ujson - imported in app/service.py",
+        ));
+    Ok(())
+}
+
+#[test]
+fn runs_with_success_limited_usage_packages() -> TestResult {
+    Command::cargo_bin(APP)?
+        .arg("tests/fixtures/input/import_contexts")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "mypy-boto3-s3 - only imported for type checking, it could be a dev dependency",
+        ))
+        .stdout(predicate::str::contains(
+            "orjson - only imported optionally, the code runs without it",
+        ))
+        .stdout(predicate::str::contains("tomllib").not())
+        .stdout(predicate::str::contains("pandas").not());
+    Ok(())
+}
+
+#[test]
+fn runs_with_success_dynamic_imports() -> TestResult {
+    Command::cargo_bin(APP)?
+        .arg("tests/fixtures/input/dynamic_imports")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No unused packages found."));
+    Ok(())
+}
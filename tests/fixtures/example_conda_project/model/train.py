@@ -0,0 +1,10 @@
+import numpy as np
+import pandas as pd
+import torch
+from transformers import AutoModel
+
+
+def train(path):
+    frame = pd.read_csv(path)
+    model = AutoModel.from_pretrained("bert-base-uncased")
+    return model(torch.tensor(np.asarray(frame)))
@@ -0,0 +1,6 @@
+import pytest
+
+
+@pytest.mark.skip
+def test_fetch():
+    pass
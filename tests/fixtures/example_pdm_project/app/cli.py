@@ -0,0 +1,10 @@
+import sys
+
+import tomlkit
+from dotenv import load_dotenv
+from rich.console import Console
+
+
+def main():
+    load_dotenv()
+    Console().print(tomlkit.dumps({"argv": sys.argv}))
@@ -0,0 +1,14 @@
+import requests
+from fastapi import FastAPI
+from pydantic import BaseModel
+
+app = FastAPI()
+
+
+class Status(BaseModel):
+    ok: bool
+
+
+@app.get("/")
+async def root() -> Status:
+    return Status(ok=requests.get("https://example.com").ok)
@@ -0,0 +1,7 @@
+import click
+import requests
+
+
+@click.command()
+def main():
+    click.echo(requests.get("https://example.com").status_code)
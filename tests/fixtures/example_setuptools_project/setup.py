@@ -0,0 +1,24 @@
+import os
+
+from setuptools import find_packages, setup
+
+HERE = os.path.dirname(__file__)
+
+REQUIRES = [
+    "requests>=2.31.0",
+    "click>=8.1",
+    'pywin32>=306; sys_platform == "win32"',
+]
+
+if __name__ == "__main__":
+    setup(
+        name="acme-lib",
+        version="0.1.0",
+        packages=find_packages(),
+        install_requires=REQUIRES + ["tenacity>=8.2"],
+        extras_require={
+            "redis": ["redis>=5.0"],
+            "yaml": ("PyYAML>=6.0",),
+            "dev": ["pytest>=7.4", "ruff"],
+        },
+    )
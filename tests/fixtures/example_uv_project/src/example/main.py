@@ -0,0 +1,11 @@
+import os
+
+import requests
+from fastapi import FastAPI
+
+app = FastAPI()
+
+
+@app.get("/")
+async def root():
+    return {"status": requests.codes.ok, "env": os.environ.get("ENV")}
@@ -0,0 +1,7 @@
+import acme
+import requests
+import yaml
+
+
+def fetch(url: str) -> dict:
+    return yaml.safe_load(requests.get(url).text)
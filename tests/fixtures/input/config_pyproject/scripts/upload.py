@@ -0,0 +1,3 @@
+import boto3
+
+boto3.client("s3").upload_file("report.csv", "reports", "report.csv")
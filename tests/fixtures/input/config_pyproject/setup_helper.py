@@ -0,0 +1,3 @@
+import yaml
+
+print(yaml.safe_dump({"name": "config-pyproject"}))
@@ -0,0 +1,8 @@
+import pytest
+
+from app import fetch
+
+
+def test_fetch():
+    with pytest.raises(Exception):
+        fetch("invalid")
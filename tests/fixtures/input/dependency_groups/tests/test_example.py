@@ -0,0 +1,3 @@
+import pytest
+
+from example import httpx
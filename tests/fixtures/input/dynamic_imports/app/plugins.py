@@ -0,0 +1,43 @@
+import importlib
+import importlib.util
+import pkgutil
+from importlib import import_module
+from typing import TYPE_CHECKING
+
+cache = importlib.import_module("redis.asyncio")
+loader = __import__("yaml")
+backends = {name: import_module("msgpack") for name in ["default"]}
+compressors = [importlib.import_module("zstandard")]
+relative = importlib.import_module(".settings", package="app")
+HAS_UJSON = importlib.util.find_spec("ujson") is not None
+os_module = import_module("os")
+name_from_variable = importlib.import_module(cache.__name__)
+
+if TYPE_CHECKING:
+    etree = import_module("lxml.etree")
+
+
+def load_worker():
+    return (lambda: __import__("celery"))()
+
+
+@pkgutil.resolve_name
+def init(dsn=importlib.import_module("sentry_sdk").init):
+    pass
+
+
+class Registry:
+    def find_spec(self, name):
+        return name
+
+
+registry = Registry()
+spec = registry.find_spec("sqlalchemy")
+template_loader = registry.get_loader("jinja2")
+
+
+def load_extras():
+    import importlib.util as import_util
+    from importlib import import_module as load
+
+    return import_util.find_spec("orjson"), load("rich")
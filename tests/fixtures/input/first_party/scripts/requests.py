@@ -0,0 +1,13 @@
+"""Replays the recorded requests against a local server, run as `python scripts/requests.py`"""
+import json
+import sys
+
+
+def main(path):
+    with open(path) as f:
+        for request in json.load(f):
+            print(request["url"])
+
+
+if __name__ == "__main__":
+    main(sys.argv[1])
@@ -0,0 +1,4 @@
+from . import markdown
+from .markdown import render
+
+import acme_plugins
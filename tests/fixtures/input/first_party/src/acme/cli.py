@@ -0,0 +1,5 @@
+from acme.markdown import render
+
+
+def main():
+    print(render("https://example.com"))
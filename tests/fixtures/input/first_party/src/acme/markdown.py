@@ -0,0 +1,7 @@
+import requests
+
+from ..acme import __name__ as package_name
+
+
+def render(url):
+    return requests.get(url).text
@@ -0,0 +1,8 @@
+import pytest
+
+from acme import cli
+
+
+def test_main():
+    with pytest.raises(TypeError):
+        cli.main(1)
@@ -0,0 +1,41 @@
+import sys
+import typing
+
+import requests
+
+if typing.TYPE_CHECKING:
+    import pandas
+    from mypy_boto3_s3 import S3Client
+
+    try:
+        import numpy
+    except ImportError:
+        pass
+
+try:
+    import orjson as json
+except (ImportError, AttributeError):
+    import ujson as json
+
+try:
+    import yaml
+except ValueError:
+    pass
+
+if sys.version_info >= (3, 11):
+    import tomllib
+else:
+    import tomli as tomllib
+
+if sys.platform.startswith("win"):
+    import colorama
+
+
+def load(path: str) -> "pandas.DataFrame":
+    import pandas
+
+    return pandas.read_csv(path)
+
+
+def fetch(url: str) -> dict:
+    return json.loads(requests.get(url).text)
@@ -0,0 +1,8 @@
+import cv2
+import yaml
+from bs4 import BeautifulSoup
+from flask import Flask
+from google.cloud import storage
+from PIL import Image
+from sklearn.linear_model import LinearRegression
+from google.api_core import retry
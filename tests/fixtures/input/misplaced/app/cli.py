@@ -0,0 +1,2 @@
+import requests
+from rich.console import Console
@@ -0,0 +1,2 @@
+import freezegun
+import pytest
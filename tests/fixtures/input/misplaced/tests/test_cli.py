@@ -0,0 +1,4 @@
+import pytest
+from freezegun import freeze_time
+
+from app import cli
@@ -0,0 +1,5 @@
+import requests
+import yaml
+from urllib3.util import Retry
+
+from app import settings
@@ -0,0 +1,3 @@
+import pytest
+
+from app.client import requests
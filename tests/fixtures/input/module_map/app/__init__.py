@@ -0,0 +1,8 @@
+import requests
+from acme_sdk import Client
+from acme_tools.retry import retry
+
+
+def fetch(url: str) -> str:
+    client = Client()
+    return retry(lambda: client.get(requests.get(url).text))
@@ -0,0 +1,54 @@
+import json
+
+try:
+    import orjson as fast_json
+except ImportError:
+    import ujson as fast_json
+finally:
+    import logging
+
+
+def get_cache():
+    import redis
+
+    return redis.Redis()
+
+
+class Worker:
+    def __init__(self):
+        from celery import Celery
+
+        self.app = Celery("worker")
+
+    async def upload(self, path):
+        async with self.lock:
+            import boto3
+
+            return boto3.client("s3").upload_file(path, "bucket", path)
+
+
+if __name__ == "__main__":
+    import click
+else:
+    for attempt in range(3):
+        with open("settings.json") as settings:
+            from httpx import Client
+    while False:
+        import numpy
+    else:
+        from rich import print
+
+
+def parse(document):
+    match document:
+        case str():
+            from lxml import etree
+
+            return etree.fromstring(document)
+        case _:
+            return json.loads(document)
+
+
+async def run():
+    async for _ in stream():
+        import anyio
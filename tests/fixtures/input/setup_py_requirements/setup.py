@@ -0,0 +1,8 @@
+from setuptools import find_packages, setup
+
+setup(
+    name="acme-service",
+    version="0.1.0",
+    packages=find_packages(),
+    install_requires=["requests>=2.31.0", "tenacity>=8.2"],
+)
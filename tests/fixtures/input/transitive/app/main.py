@@ -0,0 +1,7 @@
+import sniffio
+from fastapi import FastAPI
+from pydantic import BaseModel
+from starlette.requests import Request
+from typing_extensions import Annotated
+
+import yaml
@@ -0,0 +1,3 @@
+from google.cloud import storage
+
+client = storage.Client()
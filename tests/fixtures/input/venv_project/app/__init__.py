@@ -0,0 +1,3 @@
+import yaml
+from acme_client import api
+from google.protobuf import descriptor